@@ -1,4 +1,6 @@
 // src/ast.rs
+use std::collections::{BTreeSet, HashSet};
+
 use serde::de::Error as DeError;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value; // used by the robust Sequent deserializer
@@ -46,10 +48,278 @@ pub enum FormulaNode {
     And(Box<Formula>, Box<Formula>),
     Or(Box<Formula>, Box<Formula>),
     Imp(Box<Formula>, Box<Formula>),
+    Not(Box<Formula>),
+    Iff(Box<Formula>, Box<Formula>),
     Forall(String, Box<Formula>),
     Exists(String, Box<Formula>),
 }
 
+impl Formula {
+    /// Equal up to the names of bound variables: `∀x P(x)` and `∀y P(y)`
+    /// are alpha-equivalent, `∀x P(x)` and `∀x P(y)` are not. Text is
+    /// compared as written. Agrees with comparing [`Formula::de_bruijn`]
+    /// forms, without building them.
+    pub fn alpha_eq(&self, other: &Formula) -> bool {
+        alpha_formula(self, other, &mut Vec::new())
+    }
+
+    /// The canonical form of the formula's alpha-equivalence class, for
+    /// hashing and comparing.
+    pub fn de_bruijn(&self) -> DbFormula {
+        db_formula(self, &mut Vec::new())
+    }
+}
+
+/// A formula with bound variables replaced by de Bruijn indices, so that
+/// alpha-equivalent formulas have equal forms: `∀x P(x)` and `∀y P(y)` are
+/// both `∀ P(#0)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DbFormula {
+    Var(String),
+    Bot,
+    Top,
+    Pred {
+        name: String,
+        args: Vec<DbTerm>,
+    },
+    And(Box<DbFormula>, Box<DbFormula>),
+    Or(Box<DbFormula>, Box<DbFormula>),
+    Imp(Box<DbFormula>, Box<DbFormula>),
+    Not(Box<DbFormula>),
+    Iff(Box<DbFormula>, Box<DbFormula>),
+    Forall(Box<DbFormula>),
+    Exists(Box<DbFormula>),
+    /// Unparsed text, kept as written.
+    Text(String),
+}
+
+/// A term inside a [`DbFormula`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DbTerm {
+    /// A bound variable, by the number of binders between it and its own;
+    /// `0` is the innermost.
+    Bound(usize),
+    Free(String),
+    Func {
+        name: String,
+        args: Vec<DbTerm>,
+    },
+    Text(String),
+}
+
+/// `bound` lists the variables bound so far, innermost last.
+fn db_formula<'a>(f: &'a Formula, bound: &mut Vec<&'a str>) -> DbFormula {
+    use FormulaNode::*;
+    let node = match f {
+        Formula::Node(n) => n,
+        Formula::Text(s) => return DbFormula::Text(s.clone()),
+    };
+    let mut bx = |g: &'a Formula| Box::new(db_formula(g, bound));
+    match node {
+        Var(x) => DbFormula::Var(x.clone()),
+        Bot => DbFormula::Bot,
+        Top => DbFormula::Top,
+        Pred { name, args } => DbFormula::Pred {
+            name: name.clone(),
+            args: args.iter().map(|t| db_term(t, bound)).collect(),
+        },
+        And(a, b) => DbFormula::And(bx(a), bx(b)),
+        Or(a, b) => DbFormula::Or(bx(a), bx(b)),
+        Imp(a, b) => DbFormula::Imp(bx(a), bx(b)),
+        Iff(a, b) => DbFormula::Iff(bx(a), bx(b)),
+        Not(a) => DbFormula::Not(bx(a)),
+        Forall(x, a) | Exists(x, a) => {
+            bound.push(x);
+            let body = Box::new(db_formula(a, bound));
+            bound.pop();
+            match node {
+                Forall(..) => DbFormula::Forall(body),
+                _ => DbFormula::Exists(body),
+            }
+        }
+    }
+}
+
+fn db_term(t: &Term, bound: &[&str]) -> DbTerm {
+    match t {
+        Term::Node(TermNode::Var(x)) => match bound.iter().rposition(|b| b == x) {
+            Some(i) => DbTerm::Bound(bound.len() - 1 - i),
+            None => DbTerm::Free(x.clone()),
+        },
+        Term::Node(TermNode::Func { name, args }) => DbTerm::Func {
+            name: name.clone(),
+            args: args.iter().map(|a| db_term(a, bound)).collect(),
+        },
+        Term::Text(s) => DbTerm::Text(s.clone()),
+    }
+}
+
+/// `env` pairs the variables bound on each side, innermost last.
+fn alpha_formula<'a>(a: &'a Formula, b: &'a Formula, env: &mut Vec<(&'a str, &'a str)>) -> bool {
+    use FormulaNode::*;
+    let (Formula::Node(a), Formula::Node(b)) = (a, b) else {
+        return a == b;
+    };
+    match (a, b) {
+        (Var(x), Var(y)) => x == y,
+        (Bot, Bot) | (Top, Top) => true,
+        (Pred { name: f, args: xs }, Pred { name: g, args: ys }) => {
+            f == g && xs.len() == ys.len() && xs.iter().zip(ys).all(|(x, y)| alpha_term(x, y, env))
+        }
+        (And(a1, a2), And(b1, b2))
+        | (Or(a1, a2), Or(b1, b2))
+        | (Imp(a1, a2), Imp(b1, b2))
+        | (Iff(a1, a2), Iff(b1, b2)) => alpha_formula(a1, b1, env) && alpha_formula(a2, b2, env),
+        (Not(a), Not(b)) => alpha_formula(a, b, env),
+        (Forall(x, a), Forall(y, b)) | (Exists(x, a), Exists(y, b)) => {
+            env.push((x, y));
+            let eq = alpha_formula(a, b, env);
+            env.pop();
+            eq
+        }
+        _ => false,
+    }
+}
+
+fn alpha_term(a: &Term, b: &Term, env: &[(&str, &str)]) -> bool {
+    match (a, b) {
+        (Term::Node(TermNode::Var(x)), Term::Node(TermNode::Var(y))) => {
+            // Bound by the same quantifier, or both free with the same name.
+            let i = env.iter().rposition(|(l, _)| l == x);
+            let j = env.iter().rposition(|(_, r)| r == y);
+            i == j && (i.is_some() || x == y)
+        }
+        (
+            Term::Node(TermNode::Func { name: f, args: xs }),
+            Term::Node(TermNode::Func { name: g, args: ys }),
+        ) => {
+            f == g && xs.len() == ys.len() && xs.iter().zip(ys).all(|(x, y)| alpha_term(x, y, env))
+        }
+        _ => a == b,
+    }
+}
+
+impl Term {
+    /// The variables occurring in the term; a term binds none. Text is
+    /// opaque and has none; resolve it first.
+    pub fn free_vars(&self) -> BTreeSet<String> {
+        let mut out = BTreeSet::new();
+        term_vars(self, &mut out);
+        out
+    }
+
+    /// `self[t/x]`.
+    pub fn subst(&self, x: &str, t: &Term) -> Term {
+        match self {
+            Term::Node(TermNode::Var(y)) if y == x => t.clone(),
+            Term::Node(TermNode::Func { name, args }) => Term::Node(TermNode::Func {
+                name: name.clone(),
+                args: args.iter().map(|a| a.subst(x, t)).collect(),
+            }),
+            _ => self.clone(),
+        }
+    }
+}
+
+fn term_vars(t: &Term, out: &mut BTreeSet<String>) {
+    match t {
+        Term::Node(TermNode::Var(x)) => {
+            out.insert(x.clone());
+        }
+        Term::Node(TermNode::Func { args, .. }) => args.iter().for_each(|a| term_vars(a, out)),
+        Term::Text(_) => {}
+    }
+}
+
+impl Formula {
+    /// The term variables occurring free. Text is opaque and has none.
+    pub fn free_vars(&self) -> BTreeSet<String> {
+        use FormulaNode::*;
+        let Formula::Node(node) = self else {
+            return BTreeSet::new();
+        };
+        match node {
+            Var(_) | Bot | Top => BTreeSet::new(),
+            Pred { args, .. } => args.iter().flat_map(Term::free_vars).collect(),
+            And(a, b) | Or(a, b) | Imp(a, b) | Iff(a, b) => {
+                let mut vs = a.free_vars();
+                vs.extend(b.free_vars());
+                vs
+            }
+            Not(a) => a.free_vars(),
+            Forall(x, a) | Exists(x, a) => {
+                let mut vs = a.free_vars();
+                vs.remove(x);
+                vs
+            }
+        }
+    }
+
+    /// The variables bound by a quantifier anywhere in the formula.
+    pub fn bound_vars(&self) -> BTreeSet<String> {
+        use FormulaNode::*;
+        let Formula::Node(node) = self else {
+            return BTreeSet::new();
+        };
+        match node {
+            Var(_) | Bot | Top | Pred { .. } => BTreeSet::new(),
+            And(a, b) | Or(a, b) | Imp(a, b) | Iff(a, b) => {
+                let mut vs = a.bound_vars();
+                vs.extend(b.bound_vars());
+                vs
+            }
+            Not(a) => a.bound_vars(),
+            Forall(x, a) | Exists(x, a) => {
+                let mut vs = a.bound_vars();
+                vs.insert(x.clone());
+                vs
+            }
+        }
+    }
+
+    /// `self[t/x]`: replace the free occurrences of `x` by `t`, renaming
+    /// bound variables that would capture a variable of `t`. Fresh names
+    /// add primes (`y'`, `y''`, ...). Text is returned unchanged.
+    pub fn subst(&self, x: &str, t: &Term) -> Formula {
+        use FormulaNode::*;
+        let Formula::Node(node) = self else {
+            return self.clone();
+        };
+        let bx = |a: &Formula| Box::new(a.subst(x, t));
+        Formula::Node(match node {
+            Var(_) | Bot | Top => node.clone(),
+            Pred { name, args } => Pred {
+                name: name.clone(),
+                args: args.iter().map(|a| a.subst(x, t)).collect(),
+            },
+            And(a, b) => And(bx(a), bx(b)),
+            Or(a, b) => Or(bx(a), bx(b)),
+            Imp(a, b) => Imp(bx(a), bx(b)),
+            Iff(a, b) => Iff(bx(a), bx(b)),
+            Not(a) => Not(bx(a)),
+            Forall(y, a) | Exists(y, a) => {
+                let (y, a) = if y != x && t.free_vars().contains(y) && a.free_vars().contains(x) {
+                    let mut avoid = t.free_vars();
+                    avoid.extend(a.free_vars());
+                    let mut z = format!("{y}'");
+                    while avoid.contains(&z) {
+                        z.push('\'');
+                    }
+                    let renamed = a.subst(y, &Term::Node(TermNode::Var(z.clone())));
+                    (z, renamed)
+                } else {
+                    (y.clone(), (**a).clone())
+                };
+                let body = if y == x { a } else { a.subst(x, t) };
+                match node {
+                    Forall(..) => Forall(y, Box::new(body)),
+                    _ => Exists(y, Box::new(body)),
+                }
+            }
+        })
+    }
+}
+
 /// ============================
 /// Sequents
 /// ============================
@@ -57,8 +327,37 @@ pub enum FormulaNode {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Sequent {
     pub ctx: Vec<Formula>,
-    /// Canonical output field name is `thm`; we accept many aliases on input.
-    pub thm: Formula,
+    /// The succedent: exactly one formula in intuitionistic proofs, any
+    /// number in classical ones. Written as `thm` when it has one formula
+    /// and as `succ` otherwise; we accept many aliases on input.
+    pub succ: Vec<Formula>,
+}
+
+impl Sequent {
+    /// `ctx ⊢ thm`, the intuitionistic shape.
+    pub fn new(ctx: Vec<Formula>, thm: Formula) -> Self {
+        Sequent {
+            ctx,
+            succ: vec![thm],
+        }
+    }
+
+    /// The single formula on the right, if there is exactly one.
+    pub fn thm(&self) -> Option<&Formula> {
+        match self.succ.as_slice() {
+            [f] => Some(f),
+            _ => None,
+        }
+    }
+
+    /// The term variables free in any formula of the sequent.
+    pub fn free_vars(&self) -> BTreeSet<String> {
+        self.ctx
+            .iter()
+            .chain(&self.succ)
+            .flat_map(Formula::free_vars)
+            .collect()
+    }
 }
 
 impl<'de> Deserialize<'de> for Sequent {
@@ -85,20 +384,16 @@ impl<'de> Deserialize<'de> for Sequent {
             }
         }
 
-        // Parse thm as either a Formula or a single-element array [Formula].
-        fn parse_thm<E: DeError>(v: Value) -> Result<Formula, E> {
+        // Parse the succedent as either a single Formula or an array of them.
+        fn parse_succ<E: DeError>(v: Value) -> Result<Vec<Formula>, E> {
             match v {
-                Value::Array(mut items) => match items.len() {
-                    1 => {
-                        let only = items.remove(0);
-                        serde_json::from_value::<Formula>(only).map_err(E::custom)
-                    }
-                    0 => Err(E::custom("succedent/thm was an empty array")),
-                    _ => Err(E::custom(
-                        "succedent/thm must be a formula or a single-element array",
-                    )),
-                },
-                other => serde_json::from_value::<Formula>(other).map_err(E::custom),
+                Value::Array(items) => items
+                    .into_iter()
+                    .map(|x| serde_json::from_value::<Formula>(x).map_err(E::custom))
+                    .collect(),
+                other => Ok(vec![
+                    serde_json::from_value::<Formula>(other).map_err(E::custom)?
+                ]),
             }
         }
 
@@ -109,23 +404,20 @@ impl<'de> Deserialize<'de> for Sequent {
         //  4) { ctx?: [...]/formula, thm|goal|rhs|succ|conclusion|... }
         let seq = match v {
             // 1) Shorthand string for the whole sequent.
-            Value::String(s) => Sequent {
-                ctx: Vec::new(),
-                thm: Formula::Text(s),
-            },
+            Value::String(s) => Sequent::new(Vec::new(), Formula::Text(s)),
 
             // 2) and 3) Tuple forms
             Value::Array(mut arr) => match arr.len() {
                 2 => {
-                    let thm = parse_thm::<D::Error>(arr.remove(1))?;
+                    let succ = parse_succ::<D::Error>(arr.remove(1))?;
                     let ctx = parse_ctx::<D::Error>(arr.remove(0))?;
-                    Sequent { ctx, thm }
+                    Sequent { ctx, succ }
                 }
                 3 => {
-                    let thm = parse_thm::<D::Error>(arr.remove(2))?;
+                    let succ = parse_succ::<D::Error>(arr.remove(2))?;
                     let ctx = parse_ctx::<D::Error>(arr.remove(0))?;
                     // arr[1] is a separator like "⊢" or "=>"; ignore.
-                    Sequent { ctx, thm }
+                    Sequent { ctx, succ }
                 }
                 _ => {
                     return Err(D::Error::custom(
@@ -143,6 +435,7 @@ impl<'de> Deserialize<'de> for Sequent {
                     "rhs",
                     "succ",
                     "succedent",
+                    "right",
                     "conclusion",
                     "cons",
                     "consequent",
@@ -195,8 +488,8 @@ impl<'de> Deserialize<'de> for Sequent {
                     None => Vec::new(),
                 };
 
-                let thm = parse_thm::<D::Error>(thm_val)?;
-                Sequent { ctx, thm }
+                let succ = parse_succ::<D::Error>(thm_val)?;
+                Sequent { ctx, succ }
             }
 
             other => {
@@ -212,18 +505,40 @@ impl<'de> Deserialize<'de> for Sequent {
 
 impl Serialize for Sequent {
     fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
-        // Always write the canonical long form so the schema is stable.
+        // Always write the canonical long form so the schema is stable;
+        // single-formula succedents keep the intuitionistic `thm` key.
         #[derive(Serialize)]
         struct Full<'a> {
             ctx: &'a [Formula],
             #[serde(rename = "thm")]
             thm: &'a Formula,
         }
-        Full {
-            ctx: &self.ctx,
-            thm: &self.thm,
+        #[derive(Serialize)]
+        struct Multi<'a> {
+            ctx: &'a [Formula],
+            succ: &'a [Formula],
         }
-        .serialize(s)
+        match self.thm() {
+            Some(thm) => Full {
+                ctx: &self.ctx,
+                thm,
+            }
+            .serialize(s),
+            None => Multi {
+                ctx: &self.ctx,
+                succ: &self.succ,
+            }
+            .serialize(s),
+        }
+    }
+}
+
+impl Sequent {
+    /// The same formulas on each side, read as sets, each up to
+    /// [`Formula::alpha_eq`].
+    pub fn alpha_eq(&self, other: &Sequent) -> bool {
+        let classes = |xs: &[Formula]| xs.iter().map(Formula::de_bruijn).collect::<HashSet<_>>();
+        classes(&self.succ) == classes(&other.succ) && classes(&self.ctx) == classes(&other.ctx)
     }
 }
 
@@ -235,6 +550,24 @@ impl Serialize for Sequent {
 pub struct Proof {
     pub nodes: Vec<ProofNode>,
     pub root: String,
+    /// Omitted for intuitionistic proofs, so they keep their JSON form.
+    #[serde(default, skip_serializing_if = "Calculus::is_default")]
+    pub calculus: Calculus,
+    /// How the structural rules read contexts; omitted when they are sets.
+    #[serde(default, skip_serializing_if = "Contexts::is_default")]
+    pub contexts: Contexts,
+}
+
+impl Proof {
+    /// Look up a node by id.
+    pub fn node(&self, id: &str) -> Option<&ProofNode> {
+        self.nodes.iter().find(|n| n.id == id)
+    }
+
+    /// The node named by `root`, if present.
+    pub fn root_node(&self) -> Option<&ProofNode> {
+        self.node(&self.root)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -245,4 +578,51 @@ pub struct ProofNode {
     pub premises: Vec<String>,
     #[serde(rename = "sequent", alias = "seq")]
     pub sequent: Sequent,
+    /// The instantiation of a quantifier rule: the witness term for `∀L`
+    /// and `∃R`, the eigenvariable for `∀R` and `∃L`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub term: Option<Term>,
+}
+
+/// Which sequent calculus a proof is written in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Calculus {
+    /// LJ: every sequent has exactly one formula on the right.
+    #[default]
+    Intuitionistic,
+    /// LK: sequents may have any number of formulas on the right.
+    Classical,
+}
+
+impl Calculus {
+    fn is_default(&self) -> bool {
+        *self == Calculus::default()
+    }
+}
+
+/// The context discipline: what the two sides of a sequent are. Under
+/// multisets and lists a logical rule's premises have exactly the
+/// conclusion's contexts, less the principal formula, plus the formulas
+/// the rule adds; axioms still allow any context.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Contexts {
+    /// Order and repetition do not matter; weakening, contraction and
+    /// exchange are absorbed by every rule.
+    #[default]
+    Set,
+    /// Repetition matters: weakening and contraction must add or remove
+    /// exactly one copy of a formula.
+    Multiset,
+    /// Order matters as well: formulas move only by exchanging neighbours,
+    /// and rules act at the outer ends, the left end of the hypotheses and
+    /// the right end of the conclusions.
+    List,
+}
+
+impl Contexts {
+    fn is_default(&self) -> bool {
+        *self == Contexts::default()
+    }
 }
@@ -0,0 +1,367 @@
+// src/cert.rs
+//! Stability certificates.
+//!
+//! A certificate records one run of `transport`: which proof went in and
+//! came out, the registry slices on both ends and the rewrites applied. It
+//! is plain JSON and can be re-checked later against the two proof files.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::{
+    ast::{Proof, Sequent},
+    frag::fragility_score,
+    parse::resolve_proof,
+    registry::{Registry, RuleId, TimeSlice},
+    transport::{rules_used, rules_used_at},
+    unfold::{unfold_sequent, Connective},
+    validator::{validate_at, validate_local_wf, ValidationError},
+};
+
+/// One rewrite applied during transport.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum RewriteStep {
+    /// Cut elimination ran and removed `removed` cut nodes.
+    CutElimination { removed: usize },
+    /// `removed` cuts breaking the target slice's restrictions on `Cut`
+    /// were reduced to cuts that keep them, if any.
+    RestrictedCutElimination { removed: usize },
+    /// Structural inferences were dropped, `removed` of them.
+    StructuralElimination { removed: usize },
+    /// `connective` was unfolded, rewriting `rewritten` inferences that
+    /// used its rules.
+    Unfold {
+        connective: Connective,
+        rewritten: usize,
+    },
+    /// `expanded` inferences by `rule` were replaced by the registry's
+    /// derivation of it.
+    Expansion { rule: String, expanded: usize },
+    /// `expanded` identity axioms on compound formulas were eta-expanded
+    /// down to atoms.
+    EtaExpansion { expanded: usize },
+    /// `renamed` inferences were given the target slice's name for their
+    /// rule.
+    Rename { renamed: usize },
+}
+
+impl RewriteStep {
+    /// The end sequent this step turns `s` into. Only unfolding changes
+    /// it, and only into an equivalent sequent.
+    pub fn rewrite_end_sequent(&self, s: &Sequent) -> Sequent {
+        match self {
+            RewriteStep::CutElimination { .. }
+            | RewriteStep::RestrictedCutElimination { .. }
+            | RewriteStep::StructuralElimination { .. }
+            | RewriteStep::Expansion { .. }
+            | RewriteStep::EtaExpansion { .. }
+            | RewriteStep::Rename { .. } => s.clone(),
+            RewriteStep::Unfold { connective, .. } => unfold_sequent(s, *connective),
+        }
+    }
+}
+
+impl fmt::Display for RewriteStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RewriteStep::CutElimination { removed } => write!(f, "eliminated {removed} cut(s)"),
+            RewriteStep::RestrictedCutElimination { removed } => {
+                write!(
+                    f,
+                    "eliminated {removed} cut(s) outside the restrictions on Cut"
+                )
+            }
+            RewriteStep::StructuralElimination { removed } => {
+                write!(f, "dropped {removed} structural inference(s)")
+            }
+            RewriteStep::Unfold {
+                connective,
+                rewritten,
+            } => write!(f, "unfolded {connective} in {rewritten} inference(s)"),
+            RewriteStep::Expansion { rule, expanded } => {
+                write!(f, "expanded {expanded} use(s) of {rule}")
+            }
+            RewriteStep::EtaExpansion { expanded } => {
+                write!(f, "eta-expanded {expanded} identity axiom(s)")
+            }
+            RewriteStep::Rename { renamed } => {
+                write!(f, "renamed the rule of {renamed} inference(s)")
+            }
+        }
+    }
+}
+
+/// One hop of a transport: the rewrites that took the proof from the rules
+/// of time `from` to those of time `to`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hop {
+    pub from: u64,
+    pub to: u64,
+    pub steps: Vec<RewriteStep>,
+}
+
+impl fmt::Display for Hop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "hop t={} -> t={}: ", self.from, self.to)?;
+        if self.steps.is_empty() {
+            return f.write_str("no rewrites");
+        }
+        let steps: Vec<String> = self.steps.iter().map(RewriteStep::to_string).collect();
+        f.write_str(&steps.join(", "))
+    }
+}
+
+/// What transporting straight to the target time, skipping the
+/// intermediate hops, would lose.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DirectLoss {
+    /// Going straight fails.
+    Fails { reason: String },
+    /// Going straight gives a proof that is not valid at intermediate time
+    /// `t`, where the hop-by-hop result is.
+    InvalidAt { t: u64 },
+}
+
+impl fmt::Display for DirectLoss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DirectLoss::Fails { reason } => write!(f, "transporting directly fails: {reason}"),
+            DirectLoss::InvalidAt { t } => write!(
+                f,
+                "transporting directly gives a proof that is not valid at t={t}"
+            ),
+        }
+    }
+}
+
+/// Which of the two proofs a certificate check is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Input,
+    Output,
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Side::Input => "input",
+            Side::Output => "output",
+        })
+    }
+}
+
+/// Why a certificate does not match the proofs it is checked against.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CertError {
+    #[error("{side} proof has hash {actual}, certificate expects {expected}")]
+    HashMismatch {
+        side: Side,
+        actual: String,
+        expected: String,
+    },
+    #[error("rules used by the {side} do not match the certificate")]
+    RulesMismatch {
+        side: Side,
+        actual: Vec<RuleId>,
+        expected: Vec<RuleId>,
+    },
+    /// The proofs score `before` and `after`.
+    #[error("fragility scores do not match the certificate")]
+    FragilityMismatch { before: u64, after: u64 },
+    #[error("the hops' steps do not add up to the certificate's steps")]
+    HopMismatch,
+    /// The output is not a valid proof, at `to` when a registry is given.
+    #[error(transparent)]
+    Invalid(#[from] ValidationError),
+    #[error("output uses {rule}, which is not enabled at t={t}")]
+    RuleDisabled { rule: RuleId, t: u64 },
+    #[error("proof has no root node")]
+    NoRoot,
+    /// The output proves `actual`; the input, after the recorded
+    /// unfolding, proves `expected`.
+    #[error("output proves {actual}, but the input proves {expected}")]
+    EndSequentChanged {
+        expected: Box<Sequent>,
+        actual: Box<Sequent>,
+    },
+    #[error("registry slice at t={t} does not match the certificate")]
+    SliceMismatch { t: u64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Certificate {
+    /// See [`proof_hash`].
+    pub input_hash: String,
+    pub output_hash: String,
+    /// The rules enabled at the source time, with `t` the time asked for.
+    pub from: TimeSlice,
+    /// The rules enabled at the target time, with `t` the time asked for.
+    pub to: TimeSlice,
+    pub rules_before: Vec<RuleId>,
+    pub rules_after: Vec<RuleId>,
+    pub fragility_before: u64,
+    pub fragility_after: u64,
+    /// Every rewrite applied, in order across all hops.
+    pub steps: Vec<RewriteStep>,
+    /// The rewrites of each hop; their steps together make up `steps`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hops: Vec<Hop>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub direct_loss: Option<DirectLoss>,
+}
+
+/// `sha256:<hex>` of the proof's JSON serialization, so whitespace and key
+/// order in the file do not matter.
+pub fn proof_hash(proof: &Proof) -> String {
+    let json = serde_json::to_vec(proof).expect("proofs serialize");
+    format!("sha256:{:x}", Sha256::digest(&json))
+}
+
+fn slice(reg: &Registry, t: u64) -> TimeSlice {
+    TimeSlice {
+        t,
+        enabled_rules: sorted(reg.enabled_at(t)),
+        enabled_schemas: sorted(reg.schemas_at(t).into_iter().map(str::to_string)),
+        renames: reg
+            .slice_at(t)
+            .map(|s| s.renames.clone())
+            .unwrap_or_default(),
+        restrictions: reg
+            .slice_at(t)
+            .map(|s| s.restrictions.clone())
+            .unwrap_or_default(),
+    }
+}
+
+fn sorted<T: Ord>(items: impl IntoIterator<Item = T>) -> Vec<T> {
+    let mut v: Vec<T> = items.into_iter().collect();
+    v.sort();
+    v
+}
+
+impl Certificate {
+    pub fn new(
+        input: &Proof,
+        output: &Proof,
+        reg: &Registry,
+        from: u64,
+        to: u64,
+        hops: Vec<Hop>,
+        direct_loss: Option<DirectLoss>,
+    ) -> Self {
+        Certificate {
+            input_hash: proof_hash(input),
+            output_hash: proof_hash(output),
+            from: slice(reg, from),
+            to: slice(reg, to),
+            rules_before: sorted(rules_used_at(input, reg, from)),
+            rules_after: sorted(rules_used_at(output, reg, to)),
+            fragility_before: fragility_score(input),
+            fragility_after: fragility_score(output),
+            steps: hops.iter().flat_map(|h| h.steps.clone()).collect(),
+            hops,
+            direct_loss,
+        }
+    }
+
+    /// Re-check the certificate against the proofs it was issued for.
+    ///
+    /// The hashes, rule sets and fragility scores must match, and `output`
+    /// must be well-formed, use only rules enabled at `to`, and prove the
+    /// same end sequent as `input`, after any unfolding in `steps`. When
+    /// `reg` is given, `output` may use its rule schemas and the names the
+    /// slice at `to` gives rules, and the recorded slices must agree with
+    /// it; an output using renamed rules needs it.
+    pub fn verify(
+        &self,
+        input: &Proof,
+        output: &Proof,
+        reg: Option<&Registry>,
+    ) -> Result<(), CertError> {
+        for (side, p, expected) in [
+            (Side::Input, input, &self.input_hash),
+            (Side::Output, output, &self.output_hash),
+        ] {
+            let actual = proof_hash(p);
+            if actual != *expected {
+                return Err(CertError::HashMismatch {
+                    side,
+                    actual,
+                    expected: expected.clone(),
+                });
+            }
+        }
+        let used = |p: &Proof, t: u64| {
+            sorted(match reg {
+                Some(reg) => rules_used_at(p, reg, t),
+                None => rules_used(p),
+            })
+        };
+        for (side, p, t, expected) in [
+            (Side::Input, input, self.from.t, &self.rules_before),
+            (Side::Output, output, self.to.t, &self.rules_after),
+        ] {
+            let actual = used(p, t);
+            if actual != *expected {
+                return Err(CertError::RulesMismatch {
+                    side,
+                    actual,
+                    expected: expected.clone(),
+                });
+            }
+        }
+        let (before, after) = (fragility_score(input), fragility_score(output));
+        if (before, after) != (self.fragility_before, self.fragility_after) {
+            return Err(CertError::FragilityMismatch { before, after });
+        }
+
+        if !self.hops.is_empty() {
+            let hop_steps: Vec<&RewriteStep> = self.hops.iter().flat_map(|h| &h.steps).collect();
+            if !hop_steps.iter().copied().eq(&self.steps) {
+                return Err(CertError::HopMismatch);
+            }
+        }
+
+        match reg {
+            Some(reg) => validate_at(output, reg, self.to.t)?,
+            None => validate_local_wf(output)?,
+        }
+        if let Some(&rule) = self
+            .rules_after
+            .iter()
+            .find(|r| !self.to.enabled_rules.contains(r))
+        {
+            return Err(CertError::RuleDisabled { rule, t: self.to.t });
+        }
+
+        let a = resolve_proof(input).map_err(ValidationError::from)?;
+        let b = resolve_proof(output).map_err(ValidationError::from)?;
+        let (Some(x), Some(y)) = (a.root_node(), b.root_node()) else {
+            return Err(CertError::NoRoot);
+        };
+        let expected = self
+            .steps
+            .iter()
+            .fold(x.sequent.clone(), |s, step| step.rewrite_end_sequent(&s));
+        if !expected.alpha_eq(&y.sequent) {
+            return Err(CertError::EndSequentChanged {
+                expected: Box::new(expected),
+                actual: Box::new(y.sequent.clone()),
+            });
+        }
+
+        if let Some(reg) = reg {
+            for recorded in [&self.from, &self.to] {
+                if *recorded != slice(reg, recorded.t) {
+                    return Err(CertError::SliceMismatch { t: recorded.t });
+                }
+            }
+        }
+        Ok(())
+    }
+}
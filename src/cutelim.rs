@@ -1,79 +1,525 @@
-use crate::ast::{Proof, ProofNode};
-use std::collections::{HashMap, HashSet};
+use crate::ast::{
+    Calculus, Contexts, Formula, FormulaNode, Proof, ProofNode, Sequent, Term, TermNode,
+};
+use crate::parse::resolve_proof;
+use crate::registry::RuleId;
+use crate::validator::{
+    as_node, extend, has, is_bot, is_left_principal, subset, validate_local_wf, ValidationError,
+};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
-/// Eliminate cuts at the root repeatedly until no root Cut remains.
-pub fn cut_eliminate_root(p: &Proof) -> Proof {
-    let mut q = p.clone();
+/// Eliminate the cut at the root, if there is one.
+///
+/// The reduction needs cut-free premises, so cuts above the root are
+/// eliminated along the way. A proof whose root is not a `Cut` is returned
+/// unchanged. Input that fails [`validate_local_wf`] is refused, as by
+/// [`cut_eliminate_all`].
+pub fn cut_eliminate_root(p: &Proof) -> Result<Proof, ValidationError> {
+    validate_local_wf(p)?;
+    match p.root_node() {
+        Some(n) if RuleId::from_name(&n.rule) == Some(RuleId::Cut) => cut_eliminate_all(p),
+        _ => Ok(p.clone()),
+    }
+}
+
+/// Eliminate all cuts, keeping the root sequent.
+///
+/// Cuts are reduced from the leaves down, so every reduction sees cut-free
+/// premises. The reduction has the usual Gentzen cases: axiom cases,
+/// principal cases for each connective and commutative cases that push the
+/// cut past the other rule. Shared premises are unshared while reducing and
+/// shared again in the output; unreachable nodes are dropped.
+///
+/// Input that fails [`validate_local_wf`], such as a proof with duplicate
+/// ids or a premise cycle, is refused. Formulas written as text are parsed
+/// first, so the output uses the structured form. Only intuitionistic
+/// proofs with set contexts are reduced; classical proofs, and proofs whose
+/// structural rules count formulas, are returned unchanged. A `Cut` whose
+/// premises do not have the shape `Γ ⊢ A` and `Γ, A ⊢ C` is left in place.
+/// So is a cut on a quantified formula introduced on both sides, and one
+/// that could only move past `ForallR` or `ExistsL` by capturing their
+/// eigenvariable.
+pub fn cut_eliminate_all(p: &Proof) -> Result<Proof, ValidationError> {
+    cut_eliminate_unless(p, &|_| false)
+}
+
+/// [`cut_eliminate_all`], except that cuts whose cut formula satisfies
+/// `keep` are left in place. A cut that does not is reduced only until the
+/// cuts it turns into satisfy `keep`, so with `keep` accepting atoms,
+/// complex cuts become atomic ones.
+pub fn cut_eliminate_unless(
+    p: &Proof,
+    keep: &dyn Fn(&Formula) -> bool,
+) -> Result<Proof, ValidationError> {
+    validate_local_wf(p)?;
+    if p.calculus != Calculus::Intuitionistic
+        || p.contexts != Contexts::Set
+        || !p
+            .nodes
+            .iter()
+            .any(|n| RuleId::from_name(&n.rule) == Some(RuleId::Cut))
+    {
+        return Ok(p.clone());
+    }
+    let resolved = resolve_proof(p)?;
+    let tree = Deriv::from_proof(&resolved).expect("well-formed proofs unfold");
+    Ok(eliminate(tree, keep).into_proof())
+}
 
-    loop {
-        // find the root node
-        let root_idx = q.nodes.iter().position(|n| n.id == q.root);
-        if root_idx.is_none() {
-            break;
+/// A proof graph unfolded into a tree below its root.
+#[derive(Debug, Clone)]
+struct Deriv {
+    id: String,
+    rule: String,
+    sequent: Sequent,
+    premises: Vec<Deriv>,
+    term: Option<Term>,
+}
+
+impl Deriv {
+    /// Unfold `p` from its root; `None` on a missing id or a premise cycle.
+    fn from_proof(p: &Proof) -> Option<Deriv> {
+        fn build<'a>(
+            id: &'a str,
+            by_id: &HashMap<&'a str, &'a ProofNode>,
+            path: &mut Vec<&'a str>,
+        ) -> Option<Deriv> {
+            if path.contains(&id) {
+                return None;
+            }
+            let node = by_id.get(id)?;
+            path.push(id);
+            let premises = node
+                .premises
+                .iter()
+                .map(|pr| build(pr, by_id, path))
+                .collect::<Option<Vec<_>>>();
+            path.pop();
+            Some(Deriv {
+                id: node.id.clone(),
+                rule: node.rule.clone(),
+                sequent: node.sequent.clone(),
+                premises: premises?,
+                term: node.term.clone(),
+            })
         }
-        let root_idx = root_idx.unwrap();
 
-        if q.nodes[root_idx].rule != "Cut" || q.nodes[root_idx].premises.len() != 2 {
-            break;
+        let by_id: HashMap<&str, &ProofNode> = p.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+        build(&p.root, &by_id, &mut Vec::new())
+    }
+
+    fn kind(&self) -> Option<RuleId> {
+        RuleId::from_name(&self.rule)
+    }
+
+    fn ctx(&self) -> &[Formula] {
+        &self.sequent.ctx
+    }
+
+    /// Cut elimination only runs on intuitionistic proofs, where every
+    /// sequent has exactly one formula on the right.
+    fn thm(&self) -> &Formula {
+        self.sequent.thm().expect("intuitionistic sequent")
+    }
+
+    /// Same derivation with a larger end context. Every rule tolerates extra
+    /// context formulas, so only the conclusion needs to change, unless it
+    /// ends in `ForallR` or `ExistsL` and the new context mentions its
+    /// eigenvariable: then the eigenvariable is renamed apart first.
+    fn weaken(self, ctx: &[Formula]) -> Deriv {
+        let mut d = match (self.kind(), &self.term) {
+            (Some(RuleId::ForallR | RuleId::ExistsL), Some(Term::Node(TermNode::Var(y))))
+                if ctx.iter().any(|f| f.free_vars().contains(y)) =>
+            {
+                let y = y.clone();
+                let mut used = self.vars();
+                used.extend(ctx.iter().flat_map(Formula::free_vars));
+                let mut z = y.clone();
+                while used.contains(&z) {
+                    z.push('\'');
+                }
+                self.rename(&y, &Term::Node(TermNode::Var(z)))
+            }
+            _ => self,
+        };
+        d.sequent.ctx = ctx.to_vec();
+        d
+    }
+
+    /// The variables free in any sequent or term of the derivation.
+    fn vars(&self) -> BTreeSet<String> {
+        let mut out = self.sequent.free_vars();
+        out.extend(self.term.iter().flat_map(Term::free_vars));
+        for d in &self.premises {
+            out.extend(d.vars());
+        }
+        out
+    }
+
+    /// `self[z/y]` throughout. With `z` fresh for the derivation, every
+    /// inference still checks.
+    fn rename(self, y: &str, z: &Term) -> Deriv {
+        let sub = |fs: Vec<Formula>| -> Vec<Formula> { fs.iter().map(|f| f.subst(y, z)).collect() };
+        Deriv {
+            id: self.id,
+            rule: self.rule,
+            sequent: Sequent {
+                ctx: sub(self.sequent.ctx),
+                succ: sub(self.sequent.succ),
+            },
+            premises: self.premises.into_iter().map(|d| d.rename(y, z)).collect(),
+            term: self.term.map(|t| t.subst(y, z)),
+        }
+    }
+
+    /// Write the tree back out as a graph, sharing identical subproofs.
+    fn into_proof(self) -> Proof {
+        let mut nodes = Vec::new();
+        let mut seen = HashMap::new();
+        let mut used = HashSet::new();
+        let root = self.flatten(&mut nodes, &mut seen, &mut used);
+        Proof {
+            nodes,
+            root,
+            calculus: Calculus::Intuitionistic,
+            contexts: Contexts::Set,
         }
+    }
 
-        // Replace root with its first premise
-        let new_root = q.nodes[root_idx].premises[0].clone();
-        q.root = new_root;
+    fn flatten(
+        self,
+        nodes: &mut Vec<ProofNode>,
+        seen: &mut HashMap<String, String>,
+        used: &mut HashSet<String>,
+    ) -> String {
+        let premises: Vec<String> = self
+            .premises
+            .into_iter()
+            .map(|d| d.flatten(nodes, seen, used))
+            .collect();
 
-        // Remove the cut node
-        let cut_id = q.nodes[root_idx].id.clone();
-        q.nodes.retain(|n| n.id != cut_id);
+        let key = serde_json::to_string(&(&self.rule, &self.sequent, &premises, &self.term))
+            .expect("sequents serialize");
+        if let Some(id) = seen.get(&key) {
+            return id.clone();
+        }
 
-        prune_reachable(&mut q);
+        let mut id = self.id.clone();
+        let mut k = 1;
+        while used.contains(&id) {
+            id = format!("{}_{}", self.id, k);
+            k += 1;
+        }
+        used.insert(id.clone());
+        seen.insert(key, id.clone());
+
+        nodes.push(ProofNode {
+            id: id.clone(),
+            rule: self.rule,
+            premises,
+            sequent: self.sequent,
+            term: self.term,
+        });
+        id
+    }
+}
+
+/// Eliminate the cuts in `d` whose cut formula fails `keep`, topmost
+/// first; the cuts it accepts stay, above them or made by reducing them.
+fn eliminate(d: Deriv, keep: &dyn Fn(&Formula) -> bool) -> Deriv {
+    let Deriv {
+        id,
+        rule,
+        sequent,
+        premises,
+        term,
+    } = d;
+    let is_cut = RuleId::from_name(&rule) == Some(RuleId::Cut);
+    let kept = is_cut && premises.first().is_some_and(|left| keep(left.thm()));
+    let premises: Vec<Deriv> = premises.into_iter().map(|d| eliminate(d, keep)).collect();
+
+    if is_cut
+        && !kept
+        && premises.len() == 2
+        && sequent.thm().is_some_and(|c| premises[1].thm().alpha_eq(c))
+    {
+        if let Some(reduced) = reduce(premises[0].clone(), premises[1].clone(), &sequent.ctx, keep)
+        {
+            return reduced;
+        }
     }
 
-    q
+    Deriv {
+        id,
+        rule,
+        sequent,
+        premises,
+        term,
+    }
 }
 
-/// Eliminate all cuts by repeatedly applying `eliminate_one_cut` until no Cut remains.
-pub fn cut_eliminate_all(p: &Proof) -> Proof {
-    let mut q = p.clone();
-    loop {
-        let changed = eliminate_one_cut(&mut q);
-        if !changed {
-            break;
+/// Reduce `Cut(left: Γ' ⊢ A, right: Γ'' ⊢ C)` to a derivation of `ctx ⊢ C`,
+/// where `Γ' ⊆ ctx` and `Γ'' ⊆ ctx, A`, whose only cuts are on formulas
+/// `keep` accepts.
+///
+/// Cuts on such formulas are left in place where reduction reaches them,
+/// in the premises or made on the way, and every other cut formula is taken
+/// apart. With a `keep` accepting nothing the result is cut-free. Returns
+/// `None` when the premises do not fit the shape of a cut.
+fn reduce(
+    left: Deriv,
+    right: Deriv,
+    ctx: &[Formula],
+    keep: &dyn Fn(&Formula) -> bool,
+) -> Option<Deriv> {
+    let a = left.thm().clone();
+    let c = right.thm().clone();
+    if !subset(left.ctx(), ctx) || !subset(right.ctx(), &extend(ctx, [&a])) {
+        return None;
+    }
+
+    // The cut formula is already a hypothesis, or is never used.
+    if has(ctx, &a) || !has(right.ctx(), &a) {
+        return Some(right.weaken(ctx));
+    }
+
+    // Axiom cases on the right.
+    match right.kind()? {
+        RuleId::Id if c.alpha_eq(&a) => return Some(left.weaken(ctx)),
+        RuleId::Id | RuleId::TopR => return Some(right.weaken(ctx)),
+        RuleId::BotI if !is_bot(&a) => return Some(right.weaken(ctx)),
+        _ => {}
+    }
+
+    if keep(&a) {
+        return Some(Deriv {
+            id: right.id.clone(),
+            rule: RuleId::Cut.name().to_string(),
+            sequent: Sequent::new(ctx.to_vec(), c),
+            premises: vec![left, right],
+            term: None,
+        });
+    }
+
+    match left.kind()? {
+        // `⊥ ∈ Γ'` closes the conclusion directly.
+        RuleId::BotI if left.ctx().iter().any(is_bot) => Some(Deriv {
+            id: right.id,
+            rule: RuleId::BotI.name().to_string(),
+            sequent: Sequent::new(ctx.to_vec(), c),
+            premises: Vec::new(),
+            term: None,
+        }),
+        // The cut formula is a side formula on the left: permute upwards.
+        RuleId::AndL1
+        | RuleId::AndL2
+        | RuleId::OrL
+        | RuleId::ImpL
+        | RuleId::NotL
+        | RuleId::IffL
+        | RuleId::ForallL
+        | RuleId::ExistsL
+        | RuleId::Cut => commute_left(left, right, ctx, keep),
+        // The cut formula was just introduced on the left.
+        RuleId::AndR
+        | RuleId::Or1
+        | RuleId::Or2
+        | RuleId::ImpR
+        | RuleId::NotR
+        | RuleId::IffR
+        | RuleId::ForallR
+        | RuleId::ExistsR
+        | RuleId::TopR => {
+            if is_principal(&a, &right) {
+                principal(left, right, ctx, keep)
+            } else {
+                commute_right(left, right, ctx, keep)
+            }
         }
+        // Structural rules only drop formulas here: cut against the premise.
+        RuleId::WeakL
+        | RuleId::WeakR
+        | RuleId::ContrL
+        | RuleId::ContrR
+        | RuleId::ExchL
+        | RuleId::ExchR => reduce(left.premises.into_iter().next()?, right, ctx, keep),
+        RuleId::Id | RuleId::BotI | RuleId::OrR => None,
     }
-    q
 }
 
-/// Try to eliminate a single cut anywhere in the proof.
-/// Returns true if a cut was removed, false if none found.
-fn eliminate_one_cut(p: &mut Proof) -> bool {
-    if let Some(cut_node) = p.nodes.iter().find(|n| n.rule == "Cut").cloned() {
-        if cut_node.id == p.root && !cut_node.premises.is_empty() {
-            p.root = cut_node.premises[0].clone();
+/// Does `right` introduce the cut formula `a` on the left of `⊢`?
+fn is_principal(a: &Formula, right: &Deriv) -> bool {
+    let prems: Vec<&Sequent> = right.premises.iter().map(|d| &d.sequent).collect();
+    right
+        .kind()
+        .is_some_and(|k| is_left_principal(k, a, &right.sequent, &prems, right.term.as_ref()))
+}
+
+/// Principal cases: the cut formula is introduced on both sides, so the cut
+/// is replaced by cuts on its immediate subformulas. Since left rules may
+/// keep their principal formula, the right premises are first cut against
+/// `left` themselves.
+fn principal(
+    left: Deriv,
+    right: Deriv,
+    ctx: &[Formula],
+    keep: &dyn Fn(&Formula) -> bool,
+) -> Option<Deriv> {
+    let a = left.thm().clone();
+    let mut lp = left.premises.clone();
+    let mut rp = right.premises.clone();
+
+    match (as_node(&a)?, left.kind()?, right.kind()?) {
+        (FormulaNode::And(x, _), RuleId::AndR, RuleId::AndL1) => {
+            let f = reduce(left, rp.remove(0), &extend(ctx, [&**x]), keep)?;
+            reduce(lp.remove(0), f, ctx, keep)
+        }
+        (FormulaNode::And(_, y), RuleId::AndR, RuleId::AndL2) => {
+            let f = reduce(left, rp.remove(0), &extend(ctx, [&**y]), keep)?;
+            reduce(lp.remove(1), f, ctx, keep)
+        }
+        (FormulaNode::Or(x, _), RuleId::Or1, RuleId::OrL) => {
+            let f = reduce(left, rp.remove(0), &extend(ctx, [&**x]), keep)?;
+            reduce(lp.remove(0), f, ctx, keep)
+        }
+        (FormulaNode::Or(_, y), RuleId::Or2, RuleId::OrL) => {
+            let f = reduce(left, rp.remove(1), &extend(ctx, [&**y]), keep)?;
+            reduce(lp.remove(0), f, ctx, keep)
+        }
+        (FormulaNode::Imp(_, y), RuleId::ImpR, RuleId::ImpL) => {
+            let f2 = reduce(left.clone(), rp.remove(1), &extend(ctx, [&**y]), keep)?;
+            let f1 = reduce(left, rp.remove(0), ctx, keep)?;
+            // Γ ⊢ A and Γ, A ⊢ B give Γ ⊢ B, which then feeds Γ, B ⊢ C.
+            let mid = reduce(f1, lp.remove(0), ctx, keep)?;
+            reduce(mid, f2, ctx, keep)
+        }
+        (FormulaNode::Not(_), RuleId::NotR, RuleId::NotL) => {
+            // Γ ⊢ A and Γ, A ⊢ ⊥ give Γ ⊢ ⊥, from which C follows.
+            let c = right.thm().clone();
+            let id = right.id.clone();
+            let f = reduce(left, rp.remove(0), ctx, keep)?;
+            let bot = reduce(f, lp.remove(0), ctx, keep)?;
+            let bot_i = Deriv {
+                id,
+                rule: RuleId::BotI.name().to_string(),
+                sequent: Sequent::new(extend(ctx, [&Formula::Node(FormulaNode::Bot)]), c),
+                premises: Vec::new(),
+                term: None,
+            };
+            reduce(bot, bot_i, ctx, keep)
         }
-        p.nodes.retain(|n| n.id != cut_node.id);
-        prune_reachable(p);
-        return true;
+        (FormulaNode::Iff(x, y), RuleId::IffR, RuleId::IffL) => {
+            // Cut against both implications, each proved by ImpR.
+            let (xy, yx) = (imp(x, y), imp(y, x));
+            let f = reduce(left.clone(), rp.remove(0), &extend(ctx, [&xy, &yx]), keep)?;
+            let imp_r = |prem: Deriv, thm: Formula| Deriv {
+                id: left.id.clone(),
+                rule: RuleId::ImpR.name().to_string(),
+                sequent: Sequent::new(ctx.to_vec(), thm),
+                premises: vec![prem],
+                term: None,
+            };
+            let second = imp_r(lp.remove(1), yx);
+            let first = imp_r(lp.remove(0), xy.clone());
+            let g = reduce(second, f, &extend(ctx, [&xy]), keep)?;
+            reduce(first, g, ctx, keep)
+        }
+        _ => None,
     }
-    false
 }
 
-/// Keep only nodes reachable from root.
-fn prune_reachable(p: &mut Proof) {
-    let map: HashMap<String, ProofNode> =
-        p.nodes.iter().cloned().map(|n| (n.id.clone(), n)).collect();
+fn imp(a: &Formula, b: &Formula) -> Formula {
+    Formula::Node(FormulaNode::Imp(Box::new(a.clone()), Box::new(b.clone())))
+}
 
-    let mut keep: HashSet<String> = HashSet::new();
-    let mut stack = vec![p.root.clone()];
+/// Left commutative case: `left` ends in a left rule or a kept cut, so the
+/// cut moves into the premises of `left` that still prove the cut formula.
+fn commute_left(
+    left: Deriv,
+    right: Deriv,
+    ctx: &[Formula],
+    keep: &dyn Fn(&Formula) -> bool,
+) -> Option<Deriv> {
+    let a = left.thm().clone();
+    let c = right.thm().clone();
+    if !eigenvariable_fresh(&left, ctx, &c) {
+        return None;
+    }
+    // ImpL's first premise proves the antecedent, not the cut formula, as
+    // does the first premise of a kept cut, and NotL's only premise proves
+    // the negated formula.
+    let first_major = match left.kind() {
+        Some(RuleId::ImpL | RuleId::Cut) => 1,
+        Some(RuleId::NotL) => left.premises.len(),
+        _ => 0,
+    };
 
-    while let Some(id) = stack.pop() {
-        if keep.insert(id.clone()) {
-            if let Some(n) = map.get(&id) {
-                for pr in &n.premises {
-                    stack.push(pr.clone());
-                }
-            }
+    let mut premises = Vec::with_capacity(left.premises.len());
+    for (i, e) in left.premises.into_iter().enumerate() {
+        if i < first_major {
+            premises.push(e);
+            continue;
+        }
+        if !e.thm().alpha_eq(&a) {
+            return None;
+        }
+        let target = extend(ctx, e.ctx());
+        premises.push(reduce(e, right.clone(), &target, keep)?);
+    }
+
+    Some(Deriv {
+        id: left.id,
+        rule: left.rule,
+        sequent: Sequent::new(ctx.to_vec(), c),
+        premises,
+        term: left.term,
+    })
+}
+
+/// Right commutative case: `right` does not act on the cut formula, so the
+/// cut moves into each premise of `right` that uses it.
+fn commute_right(
+    left: Deriv,
+    right: Deriv,
+    ctx: &[Formula],
+    keep: &dyn Fn(&Formula) -> bool,
+) -> Option<Deriv> {
+    if right.premises.is_empty() {
+        return None;
+    }
+    let a = left.thm().clone();
+    let c = right.thm().clone();
+    if !eigenvariable_fresh(&right, ctx, &c) {
+        return None;
+    }
+
+    let mut premises = Vec::with_capacity(right.premises.len());
+    for f in right.premises {
+        let rest: Vec<&Formula> = f.ctx().iter().filter(|g| !g.alpha_eq(&a)).collect();
+        let target = extend(ctx, rest);
+        if has(f.ctx(), &a) {
+            premises.push(reduce(left.clone(), f, &target, keep)?);
+        } else {
+            premises.push(f.weaken(&target));
         }
     }
 
-    p.nodes.retain(|n| keep.contains(&n.id));
+    Some(Deriv {
+        id: right.id,
+        rule: right.rule,
+        sequent: Sequent::new(ctx.to_vec(), c),
+        premises,
+        term: right.term,
+    })
+}
+
+/// Moving `d` to the end sequent `ctx ⊢ c` keeps the eigenvariable
+/// condition of `ForallR` and `ExistsL`: the variable stays out of it.
+fn eigenvariable_fresh(d: &Deriv, ctx: &[Formula], c: &Formula) -> bool {
+    match (d.kind(), &d.term) {
+        (Some(RuleId::ForallR | RuleId::ExistsL), Some(Term::Node(TermNode::Var(y)))) => {
+            ctx.iter().chain([c]).all(|f| !f.free_vars().contains(y))
+        }
+        _ => true,
+    }
 }
@@ -0,0 +1,323 @@
+// src/derive.rs
+//! Derived rules.
+//!
+//! A registry may declare that, from time `t` on, a rule is replaced by a
+//! derivation in other rules. The rule's premises and conclusion are
+//! patterns over formula metavariables, as in a [`RuleSchema`], and the
+//! derivation is a template of steps over the same metavariables:
+//!
+//! ```json
+//! { "rule": "AndE1", "t": 1,
+//!   "metavariables": ["A", "B"],
+//!   "premises": [ { "ctx": [], "thm": "A ∧ B" } ],
+//!   "conclusion": { "ctx": [], "thm": "A" },
+//!   "steps": [
+//!     { "id": "cut", "rule": "Cut", "premises": ["#1", "proj"], "sequent": { "ctx": [], "thm": "A" } },
+//!     { "id": "proj", "rule": "AndL1", "premises": ["ax"], "sequent": { "ctx": ["A ∧ B"], "thm": "A" } },
+//!     { "id": "ax", "rule": "Id", "sequent": { "ctx": ["A"], "thm": "A" } } ] }
+//! ```
+//!
+//! The first step concludes the rule's conclusion. A step's premises name
+//! other steps, or `#n` for premise `n` of the inference being replaced.
+//! Step sequents stand inside the contexts `Γ ⊢ Δ` of that inference, which
+//! are not written. Steps carry no terms, so they cannot use the quantifier
+//! rules.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    ast::{Calculus, Proof, ProofNode, Sequent},
+    parse::{resolve_proof, resolve_sequent, ParseError},
+    registry::{Registry, RuleId},
+    schema::{Bindings, RuleSchema},
+    validator::{extend, validate_with, ValidationError},
+};
+
+/// A rule replaced by a derivation in other rules.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Derivation {
+    /// The rule replaced: a built-in rule, by name or alias, or a schema.
+    pub rule: String,
+    /// The time from which the derivation replaces the rule.
+    #[serde(default)]
+    pub t: u64,
+    /// The identifiers that stand for formulas in the patterns and steps.
+    #[serde(default)]
+    pub metavariables: Vec<String>,
+    #[serde(default)]
+    pub premises: Vec<Sequent>,
+    pub conclusion: Sequent,
+    /// The derivation, its root first.
+    pub steps: Vec<TemplateStep>,
+}
+
+/// One inference of a derivation template.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateStep {
+    pub id: String,
+    pub rule: String,
+    /// Ids of other steps, or `#n` for premise `n` of the replaced rule.
+    #[serde(default)]
+    pub premises: Vec<String>,
+    pub sequent: Sequent,
+}
+
+/// Why a derived rule could not be expanded.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ExpandError {
+    /// The proof was not well-formed to begin with.
+    #[error(transparent)]
+    Invalid(#[from] ValidationError),
+    /// An inference by the rule does not match the derivation's patterns.
+    #[error("node {node} is not an instance of the derivation of {rule}: {reason}")]
+    NoMatch {
+        node: String,
+        rule: String,
+        reason: String,
+    },
+}
+
+/// Why a derivation template is unusable.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DerivationError {
+    #[error("derivation of `{rule}` has no steps")]
+    NoSteps { rule: String },
+    #[error("derivation of `{rule}` has a pattern that does not parse")]
+    Parse {
+        rule: String,
+        #[source]
+        source: ParseError,
+    },
+    #[error("derivation of `{rule}` repeats step id `{step}`")]
+    RepeatedStep { rule: String, step: String },
+    #[error("derivation of `{rule}`: step `{step}` has unknown premise `{premise}`")]
+    UnknownPremise {
+        rule: String,
+        step: String,
+        premise: String,
+    },
+    #[error(
+        "derivation of `{rule}`: metavariable `{meta}` of step `{step}` does not occur in the rule's premises or conclusion"
+    )]
+    UnboundMetavariable {
+        rule: String,
+        step: String,
+        meta: String,
+    },
+    /// Templates carry no terms, so they cannot instantiate or introduce
+    /// quantifiers.
+    #[error("derivation of `{rule}`: step `{step}` uses {used}, which needs a term; templates have none")]
+    QuantifierStep {
+        rule: String,
+        step: String,
+        used: RuleId,
+    },
+}
+
+/// Do two rule names denote the same rule? Aliases of built-in rules do.
+pub(crate) fn same_rule(a: &str, b: &str) -> bool {
+    match (RuleId::from_name(a), RuleId::from_name(b)) {
+        (Some(x), Some(y)) => x == y,
+        (None, None) => a == b,
+        _ => false,
+    }
+}
+
+impl Derivation {
+    /// The rule's premises and conclusion, as a schema to match against.
+    fn pattern(&self) -> RuleSchema {
+        RuleSchema {
+            name: self.rule.clone(),
+            metavariables: self.metavariables.clone(),
+            premises: self.premises.clone(),
+            conclusion: self.conclusion.clone(),
+            side_conditions: Vec::new(),
+        }
+    }
+
+    /// Check that the template is usable: its patterns parse, step ids are
+    /// unique, premises refer to steps or to premises of the rule, every
+    /// metavariable of a step is bound by the rule's patterns, and no step
+    /// uses a quantifier rule, whose term a template cannot give.
+    pub fn check_well_formed(&self) -> Result<(), DerivationError> {
+        let rule = &self.rule;
+        if self.steps.is_empty() {
+            return Err(DerivationError::NoSteps { rule: rule.clone() });
+        }
+        let pattern = self.pattern();
+        let resolve = |s: &Sequent| {
+            resolve_sequent(s).map_err(|source| DerivationError::Parse {
+                rule: rule.clone(),
+                source,
+            })
+        };
+        let mut bound = HashSet::new();
+        for s in self.premises.iter().chain([&self.conclusion]) {
+            let s = resolve(s)?;
+            for f in s.ctx.iter().chain(&s.succ) {
+                bound.extend(pattern.metas_in(f).into_iter().map(str::to_string));
+            }
+        }
+
+        let mut ids = HashSet::new();
+        for step in &self.steps {
+            if !ids.insert(step.id.as_str()) {
+                return Err(DerivationError::RepeatedStep {
+                    rule: rule.clone(),
+                    step: step.id.clone(),
+                });
+            }
+        }
+        for step in &self.steps {
+            if let Some(
+                used @ (RuleId::ForallL | RuleId::ForallR | RuleId::ExistsL | RuleId::ExistsR),
+            ) = RuleId::from_name(&step.rule)
+            {
+                return Err(DerivationError::QuantifierStep {
+                    rule: rule.clone(),
+                    step: step.id.clone(),
+                    used,
+                });
+            }
+            for prem in &step.premises {
+                let known = match prem.strip_prefix('#') {
+                    Some(n) => n
+                        .parse::<usize>()
+                        .is_ok_and(|n| (1..=self.premises.len()).contains(&n)),
+                    None => ids.contains(prem.as_str()),
+                };
+                if !known {
+                    return Err(DerivationError::UnknownPremise {
+                        rule: rule.clone(),
+                        step: step.id.clone(),
+                        premise: prem.clone(),
+                    });
+                }
+            }
+            let s = resolve(&step.sequent)?;
+            for f in s.ctx.iter().chain(&s.succ) {
+                if let Some(m) = pattern
+                    .metas_in(f)
+                    .into_iter()
+                    .find(|m| !bound.contains(*m))
+                {
+                    return Err(DerivationError::UnboundMetavariable {
+                        rule: rule.clone(),
+                        step: step.id.clone(),
+                        meta: m.to_string(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Replace every inference by `d.rule` in `p` with an instance of the
+/// derivation `d`.
+///
+/// The proof must pass [`validate_with`] against `reg`; the output uses the
+/// structured form of every formula. The replaced node keeps its id and
+/// sequent and concludes the derivation; the other steps are named after
+/// it, as `{node}_{step}`.
+pub fn expand(p: &Proof, d: &Derivation, reg: &Registry) -> Result<Proof, ExpandError> {
+    validate_with(p, reg)?;
+    let resolved = resolve_proof(p).map_err(ValidationError::from)?;
+    let pattern = d.pattern();
+    let steps: Vec<Sequent> = d
+        .steps
+        .iter()
+        .map(|s| resolve_sequent(&s.sequent))
+        .collect::<Result<_, _>>()
+        .map_err(ValidationError::from)?;
+
+    let mut used: HashSet<String> = resolved.nodes.iter().map(|n| n.id.clone()).collect();
+    let mut fresh = |base: &str| {
+        let mut id = base.to_string();
+        let mut k = 1;
+        while used.contains(&id) {
+            id = format!("{base}_{k}");
+            k += 1;
+        }
+        used.insert(id.clone());
+        id
+    };
+
+    let mut nodes = Vec::with_capacity(resolved.nodes.len());
+    for n in &resolved.nodes {
+        if !same_rule(&n.rule, &d.rule) {
+            nodes.push(n.clone());
+            continue;
+        }
+        let prems: Vec<&Sequent> = n
+            .premises
+            .iter()
+            .map(|id| &resolved.node(id).expect("validated").sequent)
+            .collect();
+        let b = pattern
+            .instance(resolved.calculus, resolved.contexts, &n.sequent, &prems)
+            .map_err(|reason| ExpandError::NoMatch {
+                node: n.id.clone(),
+                rule: d.rule.clone(),
+                reason,
+            })?;
+
+        let ids: HashMap<&str, String> = d
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                let id = match i {
+                    0 => n.id.clone(),
+                    _ => fresh(&format!("{}_{}", n.id, s.id)),
+                };
+                (s.id.as_str(), id)
+            })
+            .collect();
+        for (i, (step, pat)) in d.steps.iter().zip(&steps).enumerate() {
+            let premises = step
+                .premises
+                .iter()
+                .map(|prem| match prem.strip_prefix('#') {
+                    Some(k) => n.premises[k.parse::<usize>().expect("checked") - 1].clone(),
+                    None => ids[prem.as_str()].clone(),
+                })
+                .collect();
+            nodes.push(ProofNode {
+                id: ids[step.id.as_str()].clone(),
+                rule: step.rule.clone(),
+                sequent: match i {
+                    0 => n.sequent.clone(),
+                    _ => place(&pattern, pat, &b, &n.sequent, resolved.calculus),
+                },
+                premises,
+                term: None,
+            });
+        }
+    }
+
+    Ok(Proof { nodes, ..resolved })
+}
+
+/// The step sequent `pat`, instantiated inside the contexts of `outer`.
+fn place(
+    schema: &RuleSchema,
+    pat: &Sequent,
+    b: &Bindings,
+    outer: &Sequent,
+    calculus: Calculus,
+) -> Sequent {
+    let inst = |fs: &[_]| -> Vec<_> { fs.iter().map(|f| schema.instantiate(f, b)).collect() };
+    let (ctx, succ) = (inst(&pat.ctx), inst(&pat.succ));
+    Sequent {
+        ctx: extend(&outer.ctx, &ctx),
+        succ: match calculus {
+            Calculus::Intuitionistic if succ.is_empty() => outer.succ.clone(),
+            Calculus::Intuitionistic => succ,
+            Calculus::Classical => extend(&outer.succ, &succ),
+        },
+    }
+}
@@ -0,0 +1,243 @@
+// src/eta.rs
+//! Eta-expansion of identity axioms.
+//!
+//! An `Id` inference on a compound formula can be replaced by a derivation
+//! that takes the formula apart on the left and puts it together again on
+//! the right, so that `Id` is only used on atoms:
+//!
+//! ```text
+//! A ∧ B  ↦ AndR over AndL1 and AndL2
+//! A ∨ B  ↦ OrL over Or1 and Or2
+//! A ⇒ B  ↦ ImpR over ImpL
+//! ¬A     ↦ NotR over NotL
+//! A ⇔ B  ↦ IffR over IffL and ImpL, twice
+//! ∀x A   ↦ ForallR over ForallL, on a fresh variable
+//! ∃x A   ↦ ExistsL over ExistsR, on a fresh variable
+//! ⊥, ⊤   ↦ BotI, TopR
+//! ```
+//!
+//! Each component is expanded in turn, down to atoms.
+
+use std::collections::HashSet;
+
+use thiserror::Error;
+
+use crate::{
+    ast::{Calculus, Formula, FormulaNode, Proof, ProofNode, Sequent, Term, TermNode},
+    parse::resolve_proof,
+    registry::RuleId,
+    restrict::Restriction,
+    validator::{as_node, extend, has, validate_local_wf, ValidationError},
+};
+
+/// Why an identity axiom could not be expanded.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum EtaError {
+    /// The proof was not well-formed to begin with.
+    #[error(transparent)]
+    Invalid(#[from] ValidationError),
+    /// The expansion needs a rule that may not be used.
+    #[error("cannot eta-expand node {node}: it needs {rule}, which is not enabled")]
+    RuleDisabled { node: String, rule: RuleId },
+}
+
+/// Replace every `Id` inference on a compound formula in `p` by its
+/// eta-expansion, using only `rules`.
+///
+/// The proof must pass [`validate_local_wf`]; the output uses the
+/// structured form of every formula. The expanded node keeps its id and
+/// sequent; the nodes above it are named after it.
+pub fn eta_expand(p: &Proof, rules: &HashSet<RuleId>) -> Result<Proof, EtaError> {
+    validate_local_wf(p)?;
+    let resolved = resolve_proof(p).map_err(ValidationError::from)?;
+    let mut used: HashSet<String> = resolved.nodes.iter().map(|n| n.id.clone()).collect();
+
+    let mut nodes = Vec::with_capacity(resolved.nodes.len());
+    for n in &resolved.nodes {
+        let compound = RuleId::from_name(&n.rule) == Some(RuleId::Id)
+            && !Restriction::Atomic.holds(RuleId::Id, &n.sequent, &[], &n.sequent);
+        let closed = n.sequent.ctx.iter().find(|f| has(&n.sequent.succ, f));
+        let (true, Some(f)) = (compound, closed) else {
+            nodes.push(n.clone());
+            continue;
+        };
+        let mut eta = Eta {
+            rules,
+            calculus: resolved.calculus,
+            delta: &n.sequent.succ,
+            node: &n.id,
+            used: &mut used,
+            nodes: Vec::new(),
+        };
+        eta.prove(n.id.clone(), &n.sequent.ctx, f)?;
+        nodes.extend(eta.nodes);
+    }
+
+    Ok(Proof { nodes, ..resolved })
+}
+
+/// The expansion of one `Id` node, built bottom-up.
+struct Eta<'a> {
+    rules: &'a HashSet<RuleId>,
+    calculus: Calculus,
+    /// The right side of the node expanded, kept throughout when classical.
+    delta: &'a [Formula],
+    node: &'a str,
+    used: &'a mut HashSet<String>,
+    nodes: Vec<ProofNode>,
+}
+
+impl Eta<'_> {
+    /// Derive `ctx ⊢ f`, with `f ∈ ctx`, as node `id`.
+    fn prove(&mut self, id: String, ctx: &[Formula], f: &Formula) -> Result<(), EtaError> {
+        use FormulaNode as N;
+        let (rule, premises, term) = match as_node(f) {
+            Some(N::Bot) => (RuleId::BotI, Vec::new(), None),
+            Some(N::Top) => (RuleId::TopR, Vec::new(), None),
+            Some(N::And(a, b)) => {
+                let (ca, cb) = (extend(ctx, [&**a]), extend(ctx, [&**b]));
+                let pa = self.sub(&ca, a)?;
+                let pb = self.sub(&cb, b)?;
+                let l = self.step(RuleId::AndL1, ctx, a, vec![pa], None)?;
+                let r = self.step(RuleId::AndL2, ctx, b, vec![pb], None)?;
+                (RuleId::AndR, vec![l, r], None)
+            }
+            Some(N::Or(a, b)) => {
+                let (ca, cb) = (extend(ctx, [&**a]), extend(ctx, [&**b]));
+                let pa = self.sub(&ca, a)?;
+                let pb = self.sub(&cb, b)?;
+                let l = self.step(RuleId::Or1, &ca, f, vec![pa], None)?;
+                let r = self.step(RuleId::Or2, &cb, f, vec![pb], None)?;
+                (RuleId::OrL, vec![l, r], None)
+            }
+            Some(N::Imp(a, b)) => {
+                let ca = extend(ctx, [&**a]);
+                let pa = self.sub(&ca, a)?;
+                let pb = self.sub(&extend(&ca, [&**b]), b)?;
+                let mp = self.step(RuleId::ImpL, &ca, b, vec![pa, pb], None)?;
+                (RuleId::ImpR, vec![mp], None)
+            }
+            Some(N::Not(a)) => {
+                let ca = extend(ctx, [&**a]);
+                let pa = self.sub(&ca, a)?;
+                let bot = Formula::Node(N::Bot);
+                let absurd = self.step(RuleId::NotL, &ca, &bot, vec![pa], None)?;
+                (RuleId::NotR, vec![absurd], None)
+            }
+            Some(N::Iff(a, b)) => {
+                let to = self.iff_half(ctx, a, b)?;
+                let from = self.iff_half(ctx, b, a)?;
+                (RuleId::IffR, vec![to, from], None)
+            }
+            Some(N::Forall(x, a)) => {
+                let (y, ay) = self.instance(ctx, f, x, a);
+                let p = self.sub(&extend(ctx, [&ay]), &ay)?;
+                let inst = self.step(RuleId::ForallL, ctx, &ay, vec![p], Some(y.clone()))?;
+                (RuleId::ForallR, vec![inst], Some(y))
+            }
+            Some(N::Exists(x, a)) => {
+                let (y, ay) = self.instance(ctx, f, x, a);
+                let cy = extend(ctx, [&ay]);
+                let p = self.sub(&cy, &ay)?;
+                let wit = self.step(RuleId::ExistsR, &cy, f, vec![p], Some(y.clone()))?;
+                (RuleId::ExistsL, vec![wit], Some(y))
+            }
+            Some(N::Var(_) | N::Pred { .. }) | None => (RuleId::Id, Vec::new(), None),
+        };
+        self.push(id, rule, ctx, f, premises, term)
+    }
+
+    /// `Γ, A ⊢ B` from `A ⇔ B ∈ Γ`: `IffL`, then `ImpL` on `A ⇒ B`.
+    fn iff_half(&mut self, ctx: &[Formula], a: &Formula, b: &Formula) -> Result<String, EtaError> {
+        let imp = |x: &Formula, y: &Formula| {
+            Formula::Node(FormulaNode::Imp(Box::new(x.clone()), Box::new(y.clone())))
+        };
+        let ca = extend(ctx, [a]);
+        let both = extend(&ca, [&imp(a, b), &imp(b, a)]);
+        let pa = self.sub(&both, a)?;
+        let pb = self.sub(&extend(&both, [b]), b)?;
+        let mp = self.step(RuleId::ImpL, &both, b, vec![pa, pb], None)?;
+        self.step(RuleId::IffL, &ca, b, vec![mp], None)
+    }
+
+    /// A variable not free in `ctx ⊢ f`, and `a` with it for `x`.
+    fn instance(&self, ctx: &[Formula], f: &Formula, x: &str, a: &Formula) -> (Term, Formula) {
+        let free = self.sequent(ctx, f).free_vars();
+        let mut y = x.to_string();
+        while free.contains(&y) {
+            y.push('\'');
+        }
+        let y = Term::Node(TermNode::Var(y));
+        let ay = a.subst(x, &y);
+        (y, ay)
+    }
+
+    /// Derive `ctx ⊢ f` as a fresh node; returns its id.
+    fn sub(&mut self, ctx: &[Formula], f: &Formula) -> Result<String, EtaError> {
+        let id = self.fresh();
+        self.prove(id.clone(), ctx, f)?;
+        Ok(id)
+    }
+
+    /// A fresh node concluding `ctx ⊢ f` by `rule`; returns its id.
+    fn step(
+        &mut self,
+        rule: RuleId,
+        ctx: &[Formula],
+        f: &Formula,
+        premises: Vec<String>,
+        term: Option<Term>,
+    ) -> Result<String, EtaError> {
+        let id = self.fresh();
+        self.push(id.clone(), rule, ctx, f, premises, term)?;
+        Ok(id)
+    }
+
+    fn push(
+        &mut self,
+        id: String,
+        rule: RuleId,
+        ctx: &[Formula],
+        f: &Formula,
+        premises: Vec<String>,
+        term: Option<Term>,
+    ) -> Result<(), EtaError> {
+        if !self.rules.contains(&rule) {
+            return Err(EtaError::RuleDisabled {
+                node: self.node.to_string(),
+                rule,
+            });
+        }
+        self.nodes.push(ProofNode {
+            id,
+            rule: rule.name().to_string(),
+            sequent: self.sequent(ctx, f),
+            premises,
+            term,
+        });
+        Ok(())
+    }
+
+    /// `ctx ⊢ f`; classical sequents keep the right side of the node.
+    fn sequent(&self, ctx: &[Formula], f: &Formula) -> Sequent {
+        Sequent {
+            ctx: ctx.to_vec(),
+            succ: match self.calculus {
+                Calculus::Intuitionistic => vec![f.clone()],
+                Calculus::Classical => extend(self.delta, [f]),
+            },
+        }
+    }
+
+    fn fresh(&mut self) -> String {
+        let base = format!("{}_eta", self.node);
+        let mut id = base.clone();
+        let mut k = 1;
+        while self.used.contains(&id) {
+            id = format!("{base}_{k}");
+            k += 1;
+        }
+        self.used.insert(id.clone());
+        id
+    }
+}
@@ -1,16 +1,42 @@
 // src/lib.rs — central library API for proof-transport
 
 pub mod ast;
+pub mod cert;
 pub mod cutelim;
+pub mod derive;
+pub mod eta;
 pub mod frag;
+pub mod parse;
+pub mod pretty;
 pub mod registry;
+pub mod restrict;
+pub mod schema;
+pub mod structural;
 pub mod transport;
+pub mod unfold;
 pub mod validator;
+pub mod validity;
 
 // Re-export key types and functions so downstream crates & tests can use directly
 pub use ast::*;
-pub use cutelim::{cut_eliminate_all, cut_eliminate_root};
+pub use cert::{proof_hash, CertError, Certificate, DirectLoss, Hop, RewriteStep, Side};
+pub use cutelim::{cut_eliminate_all, cut_eliminate_root, cut_eliminate_unless};
+pub use derive::{expand, Derivation, DerivationError, ExpandError, TemplateStep};
+pub use eta::{eta_expand, EtaError};
 pub use frag::fragility_score;
+pub use parse::{parse_formula, parse_term, resolve_proof, ParseError};
+pub use pretty::{Charset, Printer};
 pub use registry::*;
-pub use transport::{fragility_delta, transport};
-pub use validator::validate_local_wf;
+pub use restrict::Restriction;
+pub use schema::{RuleSchema, SchemaError, SideCondition};
+pub use structural::eliminate_structural;
+pub use transport::{
+    fragility_delta, restriction_strategy, rules_used, rules_used_at, strategy, transport,
+    transport_with_certificate, RuleUse, Strategy, TransportError,
+};
+pub use unfold::{unfold, unfold_formula, unfold_sequent, Connective};
+pub use validator::{
+    unreachable_warnings, validate_all, validate_all_at, validate_all_with, validate_at,
+    validate_local_wf, validate_with, Diagnostic, Severity, ValidationError,
+};
+pub use validity::{validity_intervals, Interval, Validity};
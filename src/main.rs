@@ -1,9 +1,27 @@
-use anyhow::Result;
-use clap::{Parser, Subcommand};
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
 use serde_json::from_reader;
 use std::fs::File;
+use std::process::ExitCode;
 
-use proof_transport::{ast::Proof, frag::fragility_score, validator::validate_local_wf};
+use proof_transport::{
+    ast::Proof,
+    cert::Certificate,
+    frag::fragility_score,
+    pretty::Printer,
+    registry::Registry,
+    transport::{transport_with_certificate, TransportError},
+    validator::{unreachable_warnings, validate_all, validate_all_at, validate_all_with, Severity},
+    validity::validity_intervals,
+};
+
+/// `transport` exit status when the proof needed no rewriting.
+const EXIT_ALREADY_VALID: u8 = 3;
+/// `transport` exit status when no valid proof exists at the target time.
+const EXIT_IMPOSSIBLE: u8 = 4;
+/// `transport` exit status when its rewrites broke the proof: a bug in
+/// transport rather than in the input.
+const EXIT_INTERNAL: u8 = 5;
 
 #[derive(Parser)]
 #[command(name = "proof-transport", version)]
@@ -12,28 +30,239 @@ struct Cli {
     cmd: Cmd,
 }
 
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
 #[derive(Subcommand)]
 enum Cmd {
-    Fragility { path: String },
-    Validate { path: String },
+    Fragility {
+        path: String,
+    },
+    /// Check a proof and print every problem found.
+    Validate {
+        path: String,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+        /// Also warn about nodes not reachable from the root.
+        #[arg(long)]
+        warn_unreachable: bool,
+        /// Registry JSON whose rule schemas the proof may use.
+        #[arg(long)]
+        registry: Option<String>,
+        /// Also require every rule used to be enabled in the registry at
+        /// this time.
+        #[arg(long, requires = "registry")]
+        at: Option<u64>,
+    },
+    /// Print the proof as an indented tree of sequents.
+    Show {
+        path: String,
+        /// Use ASCII connectives (`/\`, `->`, `|-`) instead of Unicode.
+        #[arg(long)]
+        ascii: bool,
+    },
+    /// Transport a proof to the rules enabled at `--to`.
+    ///
+    /// Exit status: 0 if the proof was rewritten, 3 if it was already valid
+    /// at `--to`, 4 if transport is impossible, 5 if transport produced an
+    /// invalid proof (an internal bug), 1 on any other error.
+    Transport {
+        path: String,
+        /// Registry JSON with the time slices.
+        #[arg(long)]
+        registry: String,
+        #[arg(long)]
+        from: u64,
+        #[arg(long)]
+        to: u64,
+        /// Write the proof here instead of to stdout.
+        #[arg(short, long)]
+        out: Option<String>,
+        /// Also write a stability certificate to this file.
+        #[arg(long)]
+        cert: Option<String>,
+    },
+    /// List the registry times at which a proof is valid, valid after
+    /// transport, or cannot be transported.
+    Validity {
+        path: String,
+        /// Registry JSON with the time slices.
+        #[arg(long)]
+        registry: String,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Re-check a stability certificate against its input and output proofs.
+    VerifyCert {
+        cert: String,
+        input: String,
+        output: String,
+        /// Also check the recorded slices against this registry.
+        #[arg(long)]
+        registry: Option<String>,
+    },
 }
 
 fn load(path: &str) -> Result<Proof> {
     Ok(from_reader(File::open(path)?)?)
 }
 
-fn main() -> Result<()> {
+/// Write `p` as JSON to `out`, or to stdout.
+fn write_proof(p: &Proof, out: Option<&str>) -> Result<()> {
+    let json = serde_json::to_string_pretty(p)?;
+    match out {
+        Some(path) => std::fs::write(path, json + "\n").with_context(|| format!("writing {path}")),
+        None => {
+            println!("{json}");
+            Ok(())
+        }
+    }
+}
+
+fn write_cert(c: &Certificate, path: &str) -> Result<()> {
+    let json = serde_json::to_string_pretty(c)?;
+    std::fs::write(path, json + "\n").with_context(|| format!("writing {path}"))
+}
+
+fn main() -> Result<ExitCode> {
     let cli = Cli::parse();
     match cli.cmd {
         Cmd::Fragility { path } => {
             let p = load(&path)?;
             println!("{}", fragility_score(&p));
         }
-        Cmd::Validate { path } => {
+        Cmd::Validate {
+            path,
+            format,
+            warn_unreachable,
+            registry,
+            at,
+        } => {
+            let p = load(&path)?;
+            let reg = registry.as_deref().map(Registry::from_path).transpose()?;
+            let mut diags = match (&reg, at) {
+                (Some(reg), Some(t)) => validate_all_at(&p, reg, t),
+                (Some(reg), None) => validate_all_with(&p, reg),
+                (None, _) => validate_all(&p),
+            };
+            if warn_unreachable {
+                diags.extend(unreachable_warnings(&p));
+            }
+            match format {
+                Format::Json => println!("{}", serde_json::to_string_pretty(&diags)?),
+                Format::Text if diags.is_empty() => println!("ok"),
+                Format::Text => {
+                    for d in &diags {
+                        println!("{d}");
+                    }
+                }
+            }
+            if diags.iter().any(|d| d.severity == Severity::Error) {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Cmd::Show { path, ascii } => {
             let p = load(&path)?;
-            validate_local_wf(&p)?;
+            let printer = if ascii {
+                Printer::ascii()
+            } else {
+                Printer::unicode()
+            };
+            print!("{}", printer.proof(&p));
+        }
+        Cmd::Transport {
+            path,
+            registry,
+            from,
+            to,
+            out,
+            cert,
+        } => {
+            let p = load(&path)?;
+            let reg = Registry::from_path(&registry)?;
+
+            // The report goes to stdout unless the proof itself does.
+            let report = |line: String| {
+                if out.is_some() {
+                    println!("{line}");
+                } else {
+                    eprintln!("{line}");
+                }
+            };
+
+            let (q, c) = match transport_with_certificate(&p, &reg, from, to) {
+                Ok(r) => r,
+                Err(e @ TransportError::InvalidInput(_)) => return Err(e.into()),
+                Err(
+                    e @ (TransportError::InvalidOutput { .. }
+                    | TransportError::EndSequentChanged { .. }),
+                ) => {
+                    eprintln!("internal error: {:#}", anyhow::Error::from(e));
+                    return Ok(ExitCode::from(EXIT_INTERNAL));
+                }
+                Err(e) => {
+                    eprintln!("transport impossible: {:#}", anyhow::Error::from(e));
+                    return Ok(ExitCode::from(EXIT_IMPOSSIBLE));
+                }
+            };
+
+            if c.hops.len() > 1 {
+                for hop in &c.hops {
+                    report(hop.to_string());
+                }
+            }
+            if let Some(loss) = &c.direct_loss {
+                report(format!("warning: {loss}"));
+            }
+
+            if c.steps.is_empty() {
+                write_proof(&q, out.as_deref())?;
+                if let Some(path) = &cert {
+                    write_cert(&c, path)?;
+                }
+                report(format!("already valid at t={to}"));
+                return Ok(ExitCode::from(EXIT_ALREADY_VALID));
+            }
+
+            let delta = c.fragility_after as i64 - c.fragility_before as i64;
+            write_proof(&q, out.as_deref())?;
+            if let Some(path) = &cert {
+                write_cert(&c, path)?;
+            }
+            report(format!(
+                "transported t={from} -> t={to}; fragility delta {delta}"
+            ));
+        }
+        Cmd::Validity {
+            path,
+            registry,
+            format,
+        } => {
+            let p = load(&path)?;
+            let intervals = validity_intervals(&p, &Registry::from_path(&registry)?)?;
+            match format {
+                Format::Json => println!("{}", serde_json::to_string_pretty(&intervals)?),
+                Format::Text => {
+                    for i in &intervals {
+                        println!("{i}");
+                    }
+                }
+            }
+        }
+        Cmd::VerifyCert {
+            cert,
+            input,
+            output,
+            registry,
+        } => {
+            let c: Certificate = from_reader(File::open(&cert).with_context(|| cert.clone())?)?;
+            let reg = registry.as_deref().map(Registry::from_path).transpose()?;
+            c.verify(&load(&input)?, &load(&output)?, reg.as_ref())?;
             println!("ok");
         }
     }
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
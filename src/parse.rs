@@ -0,0 +1,472 @@
+// src/parse.rs
+//! Parser for the textual surface syntax carried by `Formula::Text` and
+//! `Term::Text`.
+//!
+//! Grammar, loosest binding first:
+//!
+//! ```text
+//! formula := imp ( IFF formula )?             ⇔ ↔ <-> <=> (right assoc)
+//! imp     := disj ( IMP imp )?                ⇒ → -> =>   (right assoc)
+//! disj    := conj ( OR conj )*                ∨ \/        (left assoc)
+//! conj    := unary ( AND unary )*             ∧ /\        (left assoc)
+//! unary   := NOT unary                        ¬ ~
+//!          | ALL ident "."? unary             ∀ forall
+//!          | EX ident "."? unary              ∃ exists
+//!          | atom
+//! atom    := "(" formula ")" | BOT | TOP      ⊥ _|_ false   ⊤ true
+//!          | ident ( "(" term ("," term)* ")" )?
+//! term    := ident ( "(" term ("," term)* ")" )?
+//! ```
+//!
+//! A bare identifier is a propositional variable; with arguments it is a
+//! predicate. A name spelled like a keyword (`forall`, `exists`, `false`,
+//! `true`) is written in backticks, as in `` `true` ∧ P(`forall`) ``.
+//! Quantifiers and negation bind as tightly as possible, so `∀x P(x) ⇒ Q`
+//! reads `(∀x P(x)) ⇒ Q`.
+
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::ast::{Formula, FormulaNode, Proof, Sequent, Term, TermNode};
+
+/// A parse failure, located by character offset into the source text and,
+/// when parsing a whole proof, by node id.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{}offset {offset}: {message}", .node.as_ref().map(|n| format!("node {n}: ")).unwrap_or_default())]
+pub struct ParseError {
+    pub node: Option<String>,
+    /// Offset in characters (not bytes) into the formula text.
+    pub offset: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(offset: usize, message: impl Into<String>) -> Self {
+        ParseError {
+            node: None,
+            offset,
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn at_node(mut self, id: &str) -> Self {
+        self.node = Some(id.to_string());
+        self
+    }
+}
+
+/// Parse a formula from its textual form.
+pub fn parse_formula(src: &str) -> Result<Formula, ParseError> {
+    let mut p = Parser::new(src)?;
+    let f = p.formula()?;
+    p.finish()?;
+    Ok(f)
+}
+
+/// Parse a term from its textual form.
+pub fn parse_term(src: &str) -> Result<Term, ParseError> {
+    let mut p = Parser::new(src)?;
+    let t = p.term()?;
+    p.finish()?;
+    Ok(t)
+}
+
+impl FromStr for Formula {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_formula(s)
+    }
+}
+
+impl FromStr for Term {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_term(s)
+    }
+}
+
+/// Replace every `Text` formula or term in `f`, at any depth, by its parse.
+pub fn resolve_formula(f: &Formula) -> Result<Formula, ParseError> {
+    let node = match f {
+        Formula::Text(s) => return parse_formula(s),
+        Formula::Node(n) => n,
+    };
+    let bx = |g: &Formula| resolve_formula(g).map(Box::new);
+    Ok(Formula::Node(match node {
+        FormulaNode::Var(_) | FormulaNode::Bot | FormulaNode::Top => node.clone(),
+        FormulaNode::Pred { name, args } => FormulaNode::Pred {
+            name: name.clone(),
+            args: args.iter().map(resolve_term).collect::<Result<_, _>>()?,
+        },
+        FormulaNode::And(a, b) => FormulaNode::And(bx(a)?, bx(b)?),
+        FormulaNode::Or(a, b) => FormulaNode::Or(bx(a)?, bx(b)?),
+        FormulaNode::Imp(a, b) => FormulaNode::Imp(bx(a)?, bx(b)?),
+        FormulaNode::Not(a) => FormulaNode::Not(bx(a)?),
+        FormulaNode::Iff(a, b) => FormulaNode::Iff(bx(a)?, bx(b)?),
+        FormulaNode::Forall(x, a) => FormulaNode::Forall(x.clone(), bx(a)?),
+        FormulaNode::Exists(x, a) => FormulaNode::Exists(x.clone(), bx(a)?),
+    }))
+}
+
+/// Replace every `Text` term in `t`, at any depth, by its parse.
+pub fn resolve_term(t: &Term) -> Result<Term, ParseError> {
+    match t {
+        Term::Text(s) => parse_term(s),
+        Term::Node(TermNode::Var(_)) => Ok(t.clone()),
+        Term::Node(TermNode::Func { name, args }) => Ok(Term::Node(TermNode::Func {
+            name: name.clone(),
+            args: args.iter().map(resolve_term).collect::<Result<_, _>>()?,
+        })),
+    }
+}
+
+/// Resolve every formula of `s`.
+pub fn resolve_sequent(s: &Sequent) -> Result<Sequent, ParseError> {
+    Ok(Sequent {
+        ctx: s
+            .ctx
+            .iter()
+            .map(resolve_formula)
+            .collect::<Result<_, _>>()?,
+        succ: s
+            .succ
+            .iter()
+            .map(resolve_formula)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Resolve every sequent and instantiation term of `proof`; errors name
+/// the offending node.
+pub fn resolve_proof(proof: &Proof) -> Result<Proof, ParseError> {
+    let mut out = proof.clone();
+    for node in &mut out.nodes {
+        node.sequent = resolve_sequent(&node.sequent).map_err(|e| e.at_node(&node.id))?;
+        if let Some(t) = &node.term {
+            node.term = Some(resolve_term(t).map_err(|e| e.at_node(&node.id))?);
+        }
+    }
+    Ok(out)
+}
+
+// ---------- lexer ----------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Tok {
+    Ident(String),
+    LParen,
+    RParen,
+    Comma,
+    Dot,
+    And,
+    Or,
+    Imp,
+    Iff,
+    Not,
+    Bot,
+    Top,
+    Forall,
+    Exists,
+}
+
+fn describe(t: &Tok) -> String {
+    match t {
+        Tok::Ident(s) => format!("identifier `{s}`"),
+        Tok::LParen => "`(`".into(),
+        Tok::RParen => "`)`".into(),
+        Tok::Comma => "`,`".into(),
+        Tok::Dot => "`.`".into(),
+        Tok::And => "`∧`".into(),
+        Tok::Or => "`∨`".into(),
+        Tok::Imp => "`⇒`".into(),
+        Tok::Iff => "`⇔`".into(),
+        Tok::Not => "`¬`".into(),
+        Tok::Bot => "`⊥`".into(),
+        Tok::Top => "`⊤`".into(),
+        Tok::Forall => "`∀`".into(),
+        Tok::Exists => "`∃`".into(),
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '\''
+}
+
+/// Words the lexer reads as connectives; as names they need backticks.
+pub(crate) const KEYWORDS: [&str; 4] = ["forall", "exists", "false", "true"];
+
+fn lex(src: &str) -> Result<Vec<(Tok, usize)>, ParseError> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut out = Vec::new();
+    let mut i = 0;
+
+    // Multi-character ASCII spellings; checked before identifiers so that
+    // `_|_` is not read as the identifier `_`.
+    const ASCII: &[(&str, Tok)] = &[
+        ("<->", Tok::Iff),
+        ("<=>", Tok::Iff),
+        ("_|_", Tok::Bot),
+        ("/\\", Tok::And),
+        ("\\/", Tok::Or),
+        ("->", Tok::Imp),
+        ("=>", Tok::Imp),
+    ];
+
+    'outer: while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        for (spelling, tok) in ASCII {
+            let n = spelling.chars().count();
+            if chars[i..].iter().take(n).copied().eq(spelling.chars()) {
+                out.push((tok.clone(), i));
+                i += n;
+                continue 'outer;
+            }
+        }
+        let tok = match c {
+            '(' => Tok::LParen,
+            ')' => Tok::RParen,
+            ',' => Tok::Comma,
+            '.' => Tok::Dot,
+            '∧' => Tok::And,
+            '∨' => Tok::Or,
+            '⇒' | '→' => Tok::Imp,
+            '⇔' | '↔' => Tok::Iff,
+            '¬' | '~' => Tok::Not,
+            '⊥' => Tok::Bot,
+            '⊤' => Tok::Top,
+            '∀' => Tok::Forall,
+            '∃' => Tok::Exists,
+            '`' => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && is_ident_char(chars[i]) {
+                    i += 1;
+                }
+                if i == start + 1 || chars.get(i) != Some(&'`') {
+                    return Err(ParseError::new(start, "expected a name between backticks"));
+                }
+                out.push((Tok::Ident(chars[start + 1..i].iter().collect()), start));
+                i += 1;
+                continue;
+            }
+            c if is_ident_char(c) => {
+                let start = i;
+                while i < chars.len() && is_ident_char(chars[i]) {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                let tok = match word.as_str() {
+                    "forall" => Tok::Forall,
+                    "exists" => Tok::Exists,
+                    "false" => Tok::Bot,
+                    "true" => Tok::Top,
+                    _ => Tok::Ident(word),
+                };
+                out.push((tok, start));
+                continue;
+            }
+            other => {
+                return Err(ParseError::new(
+                    i,
+                    format!("unexpected character `{other}`"),
+                ))
+            }
+        };
+        out.push((tok, i));
+        i += 1;
+    }
+    Ok(out)
+}
+
+// ---------- parser ----------
+
+struct Parser {
+    toks: Vec<(Tok, usize)>,
+    pos: usize,
+    /// Offset reported for "unexpected end of input".
+    end: usize,
+}
+
+impl Parser {
+    fn new(src: &str) -> Result<Self, ParseError> {
+        Ok(Parser {
+            toks: lex(src)?,
+            pos: 0,
+            end: src.chars().count(),
+        })
+    }
+
+    fn peek(&self) -> Option<&Tok> {
+        self.toks.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn offset(&self) -> usize {
+        self.toks.get(self.pos).map_or(self.end, |(_, o)| *o)
+    }
+
+    fn eat(&mut self, t: &Tok) -> bool {
+        if self.peek() == Some(t) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn unexpected(&self, wanted: &str) -> ParseError {
+        match self.peek() {
+            Some(t) => ParseError::new(
+                self.offset(),
+                format!("expected {wanted}, found {}", describe(t)),
+            ),
+            None => ParseError::new(self.end, format!("expected {wanted}, found end of input")),
+        }
+    }
+
+    fn expect(&mut self, t: &Tok) -> Result<(), ParseError> {
+        if self.eat(t) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&describe(t)))
+        }
+    }
+
+    fn finish(&self) -> Result<(), ParseError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.unexpected("end of input")),
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, ParseError> {
+        match self.peek() {
+            Some(Tok::Ident(s)) => {
+                let s = s.clone();
+                self.pos += 1;
+                Ok(s)
+            }
+            _ => Err(self.unexpected("an identifier")),
+        }
+    }
+
+    fn formula(&mut self) -> Result<Formula, ParseError> {
+        let lhs = self.imp()?;
+        if self.eat(&Tok::Iff) {
+            let rhs = self.formula()?;
+            return Ok(node(FormulaNode::Iff(Box::new(lhs), Box::new(rhs))));
+        }
+        Ok(lhs)
+    }
+
+    fn imp(&mut self) -> Result<Formula, ParseError> {
+        let lhs = self.disj()?;
+        if self.eat(&Tok::Imp) {
+            let rhs = self.imp()?;
+            return Ok(node(FormulaNode::Imp(Box::new(lhs), Box::new(rhs))));
+        }
+        Ok(lhs)
+    }
+
+    fn disj(&mut self) -> Result<Formula, ParseError> {
+        let mut lhs = self.conj()?;
+        while self.eat(&Tok::Or) {
+            let rhs = self.conj()?;
+            lhs = node(FormulaNode::Or(Box::new(lhs), Box::new(rhs)));
+        }
+        Ok(lhs)
+    }
+
+    fn conj(&mut self) -> Result<Formula, ParseError> {
+        let mut lhs = self.unary()?;
+        while self.eat(&Tok::And) {
+            let rhs = self.unary()?;
+            lhs = node(FormulaNode::And(Box::new(lhs), Box::new(rhs)));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Formula, ParseError> {
+        if self.eat(&Tok::Not) {
+            return Ok(node(FormulaNode::Not(Box::new(self.unary()?))));
+        }
+        for (tok, is_forall) in [(Tok::Forall, true), (Tok::Exists, false)] {
+            if self.eat(&tok) {
+                let x = self.ident()?;
+                self.eat(&Tok::Dot);
+                let body = Box::new(self.unary()?);
+                return Ok(node(if is_forall {
+                    FormulaNode::Forall(x, body)
+                } else {
+                    FormulaNode::Exists(x, body)
+                }));
+            }
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<Formula, ParseError> {
+        match self.peek() {
+            Some(Tok::LParen) => {
+                self.pos += 1;
+                let f = self.formula()?;
+                self.expect(&Tok::RParen)?;
+                Ok(f)
+            }
+            Some(Tok::Bot) => {
+                self.pos += 1;
+                Ok(node(FormulaNode::Bot))
+            }
+            Some(Tok::Top) => {
+                self.pos += 1;
+                Ok(node(FormulaNode::Top))
+            }
+            Some(Tok::Ident(_)) => {
+                let name = self.ident()?;
+                if self.peek() == Some(&Tok::LParen) {
+                    let args = self.args()?;
+                    Ok(node(FormulaNode::Pred { name, args }))
+                } else {
+                    Ok(node(FormulaNode::Var(name)))
+                }
+            }
+            _ => Err(self.unexpected("a formula")),
+        }
+    }
+
+    fn term(&mut self) -> Result<Term, ParseError> {
+        let name = self.ident()?;
+        if self.peek() == Some(&Tok::LParen) {
+            let args = self.args()?;
+            Ok(Term::Node(TermNode::Func { name, args }))
+        } else {
+            Ok(Term::Node(TermNode::Var(name)))
+        }
+    }
+
+    /// `( term, ... )`, possibly empty.
+    fn args(&mut self) -> Result<Vec<Term>, ParseError> {
+        self.expect(&Tok::LParen)?;
+        let mut args = Vec::new();
+        if self.eat(&Tok::RParen) {
+            return Ok(args);
+        }
+        loop {
+            args.push(self.term()?);
+            if self.eat(&Tok::RParen) {
+                return Ok(args);
+            }
+            self.expect(&Tok::Comma)?;
+        }
+    }
+}
+
+fn node(n: FormulaNode) -> Formula {
+    Formula::Node(n)
+}
@@ -0,0 +1,306 @@
+// src/pretty.rs
+//! Pretty-printing for formulas, sequents and proofs.
+//!
+//! Output uses the same surface syntax that `parse.rs` reads, with the
+//! fewest parentheses that keep the parse unchanged, so printing and
+//! parsing round-trip.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::{
+    ast::{Formula, FormulaNode, Proof, Sequent, Term, TermNode},
+    parse::KEYWORDS,
+};
+
+/// Which spelling of the connectives to print.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Charset {
+    /// `∧ ∨ ⇒ ⇔ ¬ ⊥ ⊤ ∀ ∃ ⊢`
+    #[default]
+    Unicode,
+    /// `/\ \/ -> <-> ~ _|_ true forall exists |-`
+    Ascii,
+}
+
+/// A configurable printer; `Display` impls use `Printer::default()`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Printer {
+    pub charset: Charset,
+}
+
+/// `x` as the parser reads it back: in backticks if it is a keyword.
+fn name(x: &str) -> String {
+    if KEYWORDS.contains(&x) {
+        format!("`{x}`")
+    } else {
+        x.to_string()
+    }
+}
+
+// Binding strength; a subformula is parenthesised when it binds more
+// loosely than its position requires.
+const IFF: u8 = 1;
+const IMP: u8 = 2;
+const OR: u8 = 3;
+const AND: u8 = 4;
+const UNARY: u8 = 5;
+
+impl Printer {
+    pub fn unicode() -> Self {
+        Printer {
+            charset: Charset::Unicode,
+        }
+    }
+
+    pub fn ascii() -> Self {
+        Printer {
+            charset: Charset::Ascii,
+        }
+    }
+
+    fn sym(&self, unicode: &'static str, ascii: &'static str) -> &'static str {
+        match self.charset {
+            Charset::Unicode => unicode,
+            Charset::Ascii => ascii,
+        }
+    }
+
+    pub fn term(&self, t: &Term) -> String {
+        match t {
+            Term::Text(s) => s.clone(),
+            Term::Node(TermNode::Var(x)) => name(x),
+            Term::Node(TermNode::Func { name: f, args }) => {
+                format!("{}({})", name(f), self.terms(args))
+            }
+        }
+    }
+
+    fn terms(&self, ts: &[Term]) -> String {
+        ts.iter()
+            .map(|t| self.term(t))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn formula(&self, f: &Formula) -> String {
+        self.formula_at(f, 0)
+    }
+
+    /// Print `f` in a position that needs binding strength `min`.
+    fn formula_at(&self, f: &Formula, min: u8) -> String {
+        let node = match f {
+            Formula::Node(n) => n,
+            // Text is printed as written; bracket anything but a bare name
+            // when it sits under a connective.
+            Formula::Text(s) => {
+                let atomic = s.chars().all(|c| c.is_alphanumeric() || c == '_');
+                return if min > 0 && !atomic {
+                    format!("({s})")
+                } else {
+                    s.clone()
+                };
+            }
+        };
+
+        let (prec, body) = match node {
+            FormulaNode::Var(x) => return name(x),
+            FormulaNode::Bot => return self.sym("⊥", "_|_").into(),
+            FormulaNode::Top => return self.sym("⊤", "true").into(),
+            FormulaNode::Pred { name: p, args } => {
+                return format!("{}({})", name(p), self.terms(args))
+            }
+            FormulaNode::Iff(a, b) => (
+                IFF,
+                format!(
+                    "{} {} {}",
+                    self.formula_at(a, IMP),
+                    self.sym("⇔", "<->"),
+                    self.formula_at(b, IFF)
+                ),
+            ),
+            FormulaNode::Imp(a, b) => (
+                IMP,
+                format!(
+                    "{} {} {}",
+                    self.formula_at(a, OR),
+                    self.sym("⇒", "->"),
+                    self.formula_at(b, IMP)
+                ),
+            ),
+            FormulaNode::Or(a, b) => (
+                OR,
+                format!(
+                    "{} {} {}",
+                    self.formula_at(a, OR),
+                    self.sym("∨", "\\/"),
+                    self.formula_at(b, AND)
+                ),
+            ),
+            FormulaNode::And(a, b) => (
+                AND,
+                format!(
+                    "{} {} {}",
+                    self.formula_at(a, AND),
+                    self.sym("∧", "/\\"),
+                    self.formula_at(b, UNARY)
+                ),
+            ),
+            FormulaNode::Not(a) => (
+                UNARY,
+                format!("{}{}", self.sym("¬", "~"), self.formula_at(a, UNARY)),
+            ),
+            FormulaNode::Forall(x, a) => (
+                UNARY,
+                match self.charset {
+                    Charset::Unicode => format!("∀{} {}", name(x), self.formula_at(a, UNARY)),
+                    Charset::Ascii => {
+                        format!("forall {}. {}", name(x), self.formula_at(a, UNARY))
+                    }
+                },
+            ),
+            FormulaNode::Exists(x, a) => (
+                UNARY,
+                match self.charset {
+                    Charset::Unicode => format!("∃{} {}", name(x), self.formula_at(a, UNARY)),
+                    Charset::Ascii => {
+                        format!("exists {}. {}", name(x), self.formula_at(a, UNARY))
+                    }
+                },
+            ),
+        };
+
+        if prec < min {
+            format!("({body})")
+        } else {
+            body
+        }
+    }
+
+    /// `Γ ⊢ Δ`, with both sides comma-separated.
+    pub fn sequent(&self, s: &Sequent) -> String {
+        let side = |fs: &[Formula]| {
+            fs.iter()
+                .map(|f| self.formula(f))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut out = side(&s.ctx);
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.push_str(self.sym("⊢", "|-"));
+        if !s.succ.is_empty() {
+            out.push(' ');
+            out.push_str(&side(&s.succ));
+        }
+        out
+    }
+
+    /// The proof as an indented tree from the root. A premise shared by
+    /// several nodes is printed in full once and referenced afterwards;
+    /// nodes not reachable from the root are listed at the end.
+    pub fn proof(&self, p: &Proof) -> String {
+        let mut out = String::new();
+        let mut printed = HashSet::new();
+        self.proof_node(
+            p,
+            &p.root,
+            ("", ""),
+            &mut printed,
+            &mut Vec::new(),
+            &mut out,
+        );
+
+        let unreachable: Vec<&str> = p
+            .nodes
+            .iter()
+            .map(|n| n.id.as_str())
+            .filter(|id| !printed.contains(*id))
+            .collect();
+        if !unreachable.is_empty() {
+            out.push_str(&format!("unreachable: {}\n", unreachable.join(", ")));
+        }
+        out
+    }
+
+    /// `prefix` is the indentation for this node's own line and for the
+    /// lines below it.
+    fn proof_node<'a>(
+        &self,
+        p: &'a Proof,
+        id: &'a str,
+        (first, rest): (&str, &str),
+        printed: &mut HashSet<&'a str>,
+        path: &mut Vec<&'a str>,
+        out: &mut String,
+    ) {
+        let Some(node) = p.node(id) else {
+            out.push_str(&format!("{first}{id}: (missing)\n"));
+            return;
+        };
+        if path.contains(&id) {
+            out.push_str(&format!("{first}{id}: (cycle)\n"));
+            return;
+        }
+        if !printed.insert(id) {
+            out.push_str(&format!("{first}{id}: (see above)\n"));
+            return;
+        }
+        let rule = match &node.term {
+            Some(t) => format!("{} {}", node.rule, self.term(t)),
+            None => node.rule.clone(),
+        };
+        out.push_str(&format!(
+            "{first}{id}: {}    [{rule}]\n",
+            self.sequent(&node.sequent)
+        ));
+
+        let (branch, last, pipe) = match self.charset {
+            Charset::Unicode => ("├─ ", "└─ ", "│  "),
+            Charset::Ascii => ("+- ", "`- ", "|  "),
+        };
+        path.push(id);
+        for (i, prem) in node.premises.iter().enumerate() {
+            let is_last = i + 1 == node.premises.len();
+            let (head, tail) = if is_last {
+                (last, "   ")
+            } else {
+                (branch, pipe)
+            };
+            self.proof_node(
+                p,
+                prem,
+                (&format!("{rest}{head}"), &format!("{rest}{tail}")),
+                printed,
+                path,
+                out,
+            );
+        }
+        path.pop();
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&Printer::default().term(self))
+    }
+}
+
+impl fmt::Display for Formula {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&Printer::default().formula(self))
+    }
+}
+
+impl fmt::Display for Sequent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&Printer::default().sequent(self))
+    }
+}
+
+impl fmt::Display for Proof {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&Printer::default().proof(self))
+    }
+}
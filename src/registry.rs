@@ -1,8 +1,22 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{
+    derive::{same_rule, Derivation, DerivationError},
+    restrict::Restriction,
+    schema::{check_schemas, RuleSchema, SchemaError},
+};
 
 /// Rule identifiers used throughout Phase‑1.
 /// (Names match tests & JSON exactly.)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum RuleId {
     Id,
     BotI,
@@ -12,39 +26,506 @@ pub enum RuleId {
     OrL,
     Or1,
     Or2,
+    /// Classical `∨R`: both disjuncts on the right of the premise.
+    OrR,
     ImpL,
     ImpR,
+    NotL,
+    NotR,
+    IffL,
+    IffR,
+    ForallL,
+    ForallR,
+    ExistsL,
+    ExistsR,
+    TopR,
+    WeakL,
+    WeakR,
+    ContrL,
+    ContrR,
+    ExchL,
+    ExchR,
     Cut,
 }
 
+impl RuleId {
+    /// Every rule, in declaration order.
+    pub const ALL: [RuleId; 27] = [
+        RuleId::Id,
+        RuleId::BotI,
+        RuleId::AndL1,
+        RuleId::AndL2,
+        RuleId::AndR,
+        RuleId::OrL,
+        RuleId::Or1,
+        RuleId::Or2,
+        RuleId::OrR,
+        RuleId::ImpL,
+        RuleId::ImpR,
+        RuleId::NotL,
+        RuleId::NotR,
+        RuleId::IffL,
+        RuleId::IffR,
+        RuleId::ForallL,
+        RuleId::ForallR,
+        RuleId::ExistsL,
+        RuleId::ExistsR,
+        RuleId::TopR,
+        RuleId::WeakL,
+        RuleId::WeakR,
+        RuleId::ContrL,
+        RuleId::ContrR,
+        RuleId::ExchL,
+        RuleId::ExchR,
+        RuleId::Cut,
+    ];
+
+    /// Map rule strings (as they appear in JSON/examples) to RuleId.
+    /// Names/case match tests exactly, plus a few common aliases.
+    pub fn from_name(s: &str) -> Option<RuleId> {
+        use RuleId::*;
+        Some(match s {
+            // Core spellings
+            "Id" => Id,
+            "BotI" => BotI,
+            "AndR" => AndR,
+            "AndL1" => AndL1,
+            "AndL2" => AndL2,
+            "OrL" => OrL,
+            "Or1" => Or1,
+            "Or2" => Or2,
+            "OrR" => OrR,
+            "ImpL" => ImpL,
+            "ImpR" => ImpR,
+            "NotL" => NotL,
+            "NotR" => NotR,
+            "IffL" => IffL,
+            "IffR" => IffR,
+            "ForallL" => ForallL,
+            "ForallR" => ForallR,
+            "ExistsL" => ExistsL,
+            "ExistsR" => ExistsR,
+            "TopR" => TopR,
+            "WeakL" => WeakL,
+            "WeakR" => WeakR,
+            "ContrL" => ContrL,
+            "ContrR" => ContrR,
+            "ExchL" => ExchL,
+            "ExchR" => ExchR,
+            "Cut" => Cut,
+
+            // Tolerated aliases used in examples/golden data
+            "Axiom" | "Ax" => Id,
+            "OrR1" | "∨R1" => Or1,
+            "OrR2" | "∨R2" => Or2,
+            "∨L" => OrL,
+            "∨R" => OrR,
+            "∧R" => AndR,
+            "∧L1" => AndL1,
+            "∧L2" => AndL2,
+            "→L" => ImpL,
+            "→R" => ImpR,
+            "¬L" => NotL,
+            "¬R" => NotR,
+            "↔L" | "⇔L" => IffL,
+            "↔R" | "⇔R" => IffR,
+            "∀L" => ForallL,
+            "∀R" => ForallR,
+            "∃L" => ExistsL,
+            "∃R" => ExistsR,
+            "⊤R" => TopR,
+            "WL" => WeakL,
+            "WR" => WeakR,
+            "CL" => ContrL,
+            "CR" => ContrR,
+            "Exch" | "XL" => ExchL,
+            "XR" => ExchR,
+
+            _ => return None,
+        })
+    }
+
+    /// Weakening, contraction and exchange, on either side.
+    pub fn is_structural(self) -> bool {
+        use RuleId::*;
+        matches!(self, WeakL | WeakR | ContrL | ContrR | ExchL | ExchR)
+    }
+
+    /// Canonical spelling, as written into transported proofs.
+    pub fn name(self) -> &'static str {
+        match self {
+            RuleId::Id => "Id",
+            RuleId::BotI => "BotI",
+            RuleId::AndL1 => "AndL1",
+            RuleId::AndL2 => "AndL2",
+            RuleId::AndR => "AndR",
+            RuleId::OrL => "OrL",
+            RuleId::Or1 => "Or1",
+            RuleId::Or2 => "Or2",
+            RuleId::OrR => "OrR",
+            RuleId::ImpL => "ImpL",
+            RuleId::ImpR => "ImpR",
+            RuleId::NotL => "NotL",
+            RuleId::NotR => "NotR",
+            RuleId::IffL => "IffL",
+            RuleId::IffR => "IffR",
+            RuleId::ForallL => "ForallL",
+            RuleId::ForallR => "ForallR",
+            RuleId::ExistsL => "ExistsL",
+            RuleId::ExistsR => "ExistsR",
+            RuleId::TopR => "TopR",
+            RuleId::WeakL => "WeakL",
+            RuleId::WeakR => "WeakR",
+            RuleId::ContrL => "ContrL",
+            RuleId::ContrR => "ContrR",
+            RuleId::ExchL => "ExchL",
+            RuleId::ExchR => "ExchR",
+            RuleId::Cut => "Cut",
+        }
+    }
+}
+
+impl fmt::Display for RuleId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Serialize for RuleId {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for RuleId {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(d)?;
+        RuleId::from_name(&name).ok_or_else(|| {
+            de::Error::custom(format!("unknown rule `{name}` ({})", expected_rules()))
+        })
+    }
+}
+
+/// The names `RuleId::from_name` accepts, for error messages.
+fn expected_rules() -> String {
+    let known: Vec<&str> = RuleId::ALL.iter().map(|r| r.name()).collect();
+    format!(
+        "expected one of {}, or an alias such as OrR1, ∨R1, Axiom",
+        known.join(", ")
+    )
+}
+
+/// Why a registry could not be loaded.
+#[derive(Debug, Error)]
+pub enum RegistryError {
+    #[error("reading registry {}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("loading registry {}", path.display())]
+    Load {
+        path: PathBuf,
+        #[source]
+        source: Box<RegistryError>,
+    },
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("slice t={t} names unknown rule `{name}` ({})", expected_rules())]
+    UnknownRule { name: String, t: u64 },
+    #[error(transparent)]
+    Schema(#[from] SchemaError),
+    #[error("slice t={t} enables rule schema `{name}`, which is not declared")]
+    UndeclaredSchema { t: u64, name: String },
+    #[error("slice t={t} renames {rule} to `{name}`, which names another rule")]
+    RenameClash { t: u64, rule: RuleId, name: String },
+    #[error("slice t={t} restricts {rule}, which it does not enable")]
+    RestrictsDisabled { t: u64, rule: RuleId },
+    #[error("slice t={t}: {rule} cannot have the {restriction} restriction")]
+    RestrictionInapplicable {
+        t: u64,
+        rule: RuleId,
+        restriction: Restriction,
+    },
+    #[error(transparent)]
+    Derivation(#[from] DerivationError),
+    #[error("derivation of `{rule}` uses unknown rule `{name}`")]
+    UnknownDerivationRule { rule: String, name: String },
+    #[error("rule `{rule}` has two derivations at t={t}")]
+    DuplicateDerivation { rule: String, t: u64 },
+    #[error("time slices must be in increasing order of t, found t={before} before t={after}")]
+    UnorderedSlices { before: u64, after: u64 },
+}
+
+/// Find a rule name in a slice that `RuleId` does not know, before serde
+/// reports it without saying which slice it is in.
+fn unknown_rule(v: &Value) -> Option<RegistryError> {
+    for slice in v.get("times")?.as_array()? {
+        let Some(t) = slice.get("t").and_then(Value::as_u64) else {
+            continue;
+        };
+        let enabled = slice
+            .get("enabled_rules")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str);
+        let keys = ["renames", "restrictions"]
+            .into_iter()
+            .filter_map(|k| slice.get(k)?.as_object())
+            .flat_map(|m| m.keys().map(String::as_str));
+        if let Some(name) = enabled
+            .chain(keys)
+            .find(|name| RuleId::from_name(name).is_none())
+        {
+            return Some(RegistryError::UnknownRule {
+                name: name.to_string(),
+                t,
+            });
+        }
+    }
+    None
+}
+
 /// A point-in-time rule configuration used by tests:
-/// TimeSlice { t, enabled_rules }
-#[derive(Debug, Clone, Default)]
+/// TimeSlice { t, enabled_rules, enabled_schemas, renames, restrictions }
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TimeSlice {
     pub t: u64,
     /// Tests construct this with `vec![…]`, so keep it as a Vec.
     pub enabled_rules: Vec<RuleId>,
+    /// Names of the registry's rule schemas enabled in this slice.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub enabled_schemas: Vec<String>,
+    /// The names this slice's kernel uses for built-in rules, where they
+    /// differ from ours, e.g. `{"ImpR": "→R"}`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub renames: BTreeMap<RuleId, String>,
+    /// Restrictions on enabled rules, e.g. `{"Cut": ["atomic"]}`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub restrictions: BTreeMap<RuleId, Vec<Restriction>>,
 }
 
 /// Registry holds an ordered set of time slices.
 /// Phase‑1 needs only "what is enabled at logical time t".
-#[derive(Debug, Default, Clone)]
+///
+/// The JSON form follows `schemas/registry.schema.json`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Registry {
     pub times: Vec<TimeSlice>,
+    /// Rules declared as data, in addition to the built-in ones.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<RuleSchema>,
+    /// Rules replaced by derivations in other rules from some time on.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub derivations: Vec<Derivation>,
 }
 
 impl Registry {
+    /// Parse a registry from JSON. Slices must be listed in increasing `t`
+    /// and may only enable schemas the registry declares; their renames
+    /// must not clash with other rules, and they may only restrict rules
+    /// they enable, in ways that apply to them. Derivations must only name known
+    /// rules, at most one per rule and time.
+    pub fn from_json(s: &str) -> Result<Registry, RegistryError> {
+        let value: Value = serde_json::from_str(s)?;
+        if let Some(e) = unknown_rule(&value) {
+            return Err(e);
+        }
+        let reg: Registry = serde_json::from_str(s)?;
+        check_schemas(&reg.rules)?;
+        for slice in &reg.times {
+            if let Some(name) = slice
+                .enabled_schemas
+                .iter()
+                .find(|name| reg.schema(name).is_none())
+            {
+                return Err(RegistryError::UndeclaredSchema {
+                    t: slice.t,
+                    name: name.clone(),
+                });
+            }
+        }
+        for slice in &reg.times {
+            let mut names = HashSet::new();
+            for (rule, name) in &slice.renames {
+                let clash = match RuleId::from_name(name) {
+                    Some(other) => other != *rule,
+                    None => {
+                        reg.schema(name).is_some()
+                            || !names.insert(name.as_str())
+                            || reg.rule_id(name, None).is_some_and(|other| other != *rule)
+                    }
+                };
+                if clash {
+                    return Err(RegistryError::RenameClash {
+                        t: slice.t,
+                        rule: *rule,
+                        name: name.clone(),
+                    });
+                }
+            }
+        }
+        for slice in &reg.times {
+            for (rule, restrictions) in &slice.restrictions {
+                if !slice.enabled_rules.contains(rule) {
+                    return Err(RegistryError::RestrictsDisabled {
+                        t: slice.t,
+                        rule: *rule,
+                    });
+                }
+                if let Some(r) = restrictions.iter().find(|r| !r.applies_to(*rule)) {
+                    return Err(RegistryError::RestrictionInapplicable {
+                        t: slice.t,
+                        rule: *rule,
+                        restriction: *r,
+                    });
+                }
+            }
+        }
+        for (i, d) in reg.derivations.iter().enumerate() {
+            d.check_well_formed()?;
+            let names = std::iter::once(&d.rule).chain(d.steps.iter().map(|s| &s.rule));
+            if let Some(name) = names.into_iter().find(|name| !reg.knows(name)) {
+                return Err(RegistryError::UnknownDerivationRule {
+                    rule: d.rule.clone(),
+                    name: name.clone(),
+                });
+            }
+            if reg.derivations[..i]
+                .iter()
+                .any(|e| e.t == d.t && same_rule(&e.rule, &d.rule))
+            {
+                return Err(RegistryError::DuplicateDerivation {
+                    rule: d.rule.clone(),
+                    t: d.t,
+                });
+            }
+        }
+        for pair in reg.times.windows(2) {
+            if pair[0].t >= pair[1].t {
+                return Err(RegistryError::UnorderedSlices {
+                    before: pair[0].t,
+                    after: pair[1].t,
+                });
+            }
+        }
+        Ok(reg)
+    }
+
+    /// Load a registry file such as `examples/R.json`.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Registry, RegistryError> {
+        let path = path.as_ref();
+        let s = std::fs::read_to_string(path).map_err(|source| RegistryError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Registry::from_json(&s).map_err(|source| RegistryError::Load {
+            path: path.to_path_buf(),
+            source: Box::new(source),
+        })
+    }
+
     /// Return the set of rules enabled at logical time `t`.
     /// Semantics: last slice with `slice.t <= t` wins.
     pub fn enabled_at(&self, t: u64) -> HashSet<RuleId> {
-        let mut current: &[RuleId] = &[];
-        for slice in &self.times {
-            if slice.t <= t {
-                current = &slice.enabled_rules;
-            } else {
-                break;
-            }
+        self.slice_at(t)
+            .map_or(&[][..], |s| &s.enabled_rules)
+            .iter()
+            .copied()
+            .collect()
+    }
+
+    /// The restrictions on `rule` at logical time `t`, with the same
+    /// semantics as [`Registry::enabled_at`].
+    pub fn restrictions_at(&self, rule: RuleId, t: u64) -> &[Restriction] {
+        self.slice_at(t)
+            .and_then(|s| s.restrictions.get(&rule))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Return the names of the rule schemas enabled at logical time `t`,
+    /// with the same semantics as [`Registry::enabled_at`].
+    pub fn schemas_at(&self, t: u64) -> HashSet<&str> {
+        self.slice_at(t)
+            .map_or(&[][..], |s| &s.enabled_schemas)
+            .iter()
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Is the rule called `name`, built-in or a schema, enabled at `t`?
+    /// Renamed rules count under the names of the slice at `t`.
+    pub fn is_enabled(&self, name: &str, t: u64) -> bool {
+        match self.rule_id(name, Some(t)) {
+            Some(rule) => self.enabled_at(t).contains(&rule),
+            None => self.schemas_at(t).contains(name),
         }
-        current.iter().copied().collect()
+    }
+
+    /// The derivations in force at time `t`: for each rule, the one with
+    /// the latest `t` not after it. In the order declared.
+    pub fn derivations_at(&self, t: u64) -> Vec<&Derivation> {
+        let current = |d: &Derivation| {
+            d.t <= t
+                && !self
+                    .derivations
+                    .iter()
+                    .any(|e| same_rule(&e.rule, &d.rule) && d.t < e.t && e.t <= t)
+        };
+        self.derivations.iter().filter(|d| current(d)).collect()
+    }
+
+    /// The times at which the registry changes: those of its slices and
+    /// derivations, in increasing order.
+    pub fn change_times(&self) -> Vec<u64> {
+        let mut times: Vec<u64> = self
+            .times
+            .iter()
+            .map(|s| s.t)
+            .chain(self.derivations.iter().map(|d| d.t))
+            .collect();
+        times.sort_unstable();
+        times.dedup();
+        times
+    }
+
+    /// The built-in rule called `name`, by one of our names or aliases or
+    /// by a slice's rename: the slice at `t`, or any slice when `t` is
+    /// `None`.
+    pub fn rule_id(&self, name: &str, t: Option<u64>) -> Option<RuleId> {
+        let renamed = |s: &TimeSlice| {
+            s.renames
+                .iter()
+                .find(|(_, to)| *to == name)
+                .map(|(rule, _)| *rule)
+        };
+        RuleId::from_name(name).or_else(|| match t {
+            Some(t) => self.slice_at(t).and_then(renamed),
+            None => self.times.iter().find_map(renamed),
+        })
+    }
+
+    /// The name the kernel at time `t` uses for `rule`.
+    pub fn name_at(&self, rule: RuleId, t: u64) -> &str {
+        self.slice_at(t)
+            .and_then(|s| s.renames.get(&rule))
+            .map_or(rule.name(), String::as_str)
+    }
+
+    /// Is `name` a built-in rule or a schema the registry declares?
+    fn knows(&self, name: &str) -> bool {
+        RuleId::from_name(name).is_some() || self.schema(name).is_some()
+    }
+
+    /// The rule schema called `name`, if the registry declares one.
+    pub fn schema(&self, name: &str) -> Option<&RuleSchema> {
+        self.rules.iter().find(|s| s.name == name)
+    }
+
+    /// The slice in force at time `t`, if any.
+    pub fn slice_at(&self, t: u64) -> Option<&TimeSlice> {
+        self.times.iter().take_while(|s| s.t <= t).last()
     }
 }
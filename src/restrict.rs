@@ -0,0 +1,128 @@
+// src/restrict.rs
+//! Rule restrictions.
+//!
+//! A registry slice may enable a rule only in a restricted form, by naming
+//! restrictions for it:
+//!
+//! ```json
+//! { "t": 2, "enabled_rules": ["Id", "Cut", "ImpR"],
+//!   "restrictions": { "Cut": ["atomic"], "Id": ["atomic"] } }
+//! ```
+//!
+//! An inference by the rule must then satisfy every restriction.
+//! [`validate_at`](crate::validator::validate_at) checks them, and
+//! transport rewrites the inferences that break them where it can.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ast::{Formula, FormulaNode, Proof, ProofNode, Sequent},
+    registry::{Registry, RuleId},
+    schema::is_atomic,
+    validator::{as_node, has},
+};
+
+/// A condition on the inferences of a rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Restriction {
+    /// Only on atomic formulas: the cut formula of a `Cut`, the formula
+    /// an `Id` closes.
+    Atomic,
+    /// The cut formula of a `Cut` is a subformula of the end sequent.
+    Analytic,
+}
+
+impl fmt::Display for Restriction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Restriction::Atomic => "atomic",
+            Restriction::Analytic => "analytic",
+        })
+    }
+}
+
+impl Restriction {
+    /// Can `rule` be restricted this way?
+    pub fn applies_to(self, rule: RuleId) -> bool {
+        match self {
+            Restriction::Atomic => matches!(rule, RuleId::Cut | RuleId::Id),
+            Restriction::Analytic => rule == RuleId::Cut,
+        }
+    }
+
+    /// May a restricted inference act on `f`, in a proof of `end`?
+    pub fn allows(self, f: &Formula, end: &Sequent) -> bool {
+        match self {
+            Restriction::Atomic => is_atomic(f),
+            Restriction::Analytic => end.ctx.iter().chain(&end.succ).any(|g| is_subformula(f, g)),
+        }
+    }
+
+    /// Does the inference `prems ⟹ concl` by `rule`, in a proof of `end`,
+    /// satisfy the restriction? Inferences by other rules do.
+    pub fn holds(self, rule: RuleId, concl: &Sequent, prems: &[&Sequent], end: &Sequent) -> bool {
+        match rule {
+            RuleId::Cut => cut_formula(prems).is_none_or(|a| self.allows(a, end)),
+            RuleId::Id => concl
+                .ctx
+                .iter()
+                .any(|f| has(&concl.succ, f) && self.allows(f, end)),
+            _ => true,
+        }
+    }
+}
+
+/// The formula a cut eliminates: proved by the first premise and assumed
+/// by the second.
+fn cut_formula<'a>(prems: &[&'a Sequent]) -> Option<&'a Formula> {
+    match prems {
+        [left, right] => left.succ.iter().find(|a| has(&right.ctx, a)),
+        _ => None,
+    }
+}
+
+/// Is `f` a subformula of `g`, up to alpha-equivalence? Quantifier bodies
+/// count with their bound variable free.
+fn is_subformula(f: &Formula, g: &Formula) -> bool {
+    use FormulaNode as N;
+    f.alpha_eq(g)
+        || match as_node(g) {
+            Some(N::And(x, y) | N::Or(x, y) | N::Imp(x, y) | N::Iff(x, y)) => {
+                is_subformula(f, x) || is_subformula(f, y)
+            }
+            Some(N::Not(x) | N::Forall(_, x) | N::Exists(_, x)) => is_subformula(f, x),
+            _ => false,
+        }
+}
+
+/// The inferences of a parsed, well-formed proof that break a restriction
+/// the slice at `t` puts on their rule, with that restriction.
+pub(crate) fn broken<'a>(
+    p: &'a Proof,
+    reg: &Registry,
+    t: u64,
+) -> Vec<(&'a ProofNode, RuleId, Restriction)> {
+    let Some(end) = p.root_node().map(|n| &n.sequent) else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    for n in &p.nodes {
+        let Some(rule) = reg.rule_id(&n.rule, Some(t)) else {
+            continue;
+        };
+        let prems: Vec<&Sequent> = n
+            .premises
+            .iter()
+            .filter_map(|id| p.node(id).map(|m| &m.sequent))
+            .collect();
+        for &r in reg.restrictions_at(rule, t) {
+            if !r.holds(rule, &n.sequent, &prems, end) {
+                out.push((n, rule, r));
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,418 @@
+// src/schema.rs
+//! Rules given as data.
+//!
+//! A registry may declare its own rules as schemas: premise and conclusion
+//! sequents written over formula metavariables, plus side conditions. The
+//! validator checks a node whose rule names a schema by matching its
+//! sequents against the schema, the way [`check_rule`] checks built-in
+//! rules.
+//!
+//! ```json
+//! { "name": "AndE1",
+//!   "metavariables": ["A", "B"],
+//!   "premises": [ { "ctx": [], "thm": "A ∧ B" } ],
+//!   "conclusion": { "ctx": [], "thm": "A" } }
+//! ```
+//!
+//! Every pattern stands inside the contexts `Γ ⊢ Δ` of the conclusion,
+//! which are not written. As for the built-in rules, contexts are read as
+//! sets and absorb weakening: the conclusion must contain each formula its
+//! pattern shows, and premise `n` may use `Γ` and `Δ` plus the formulas its
+//! pattern adds, and must prove the formulas on the right of its pattern.
+//! Identifiers listed as metavariables stand for any formula; everything
+//! else in a pattern is matched literally, binders by name.
+//!
+//! [`check_rule`]: crate::validator::check_rule
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    ast::{Calculus, Contexts, Formula, FormulaNode, Sequent},
+    parse::{resolve_sequent, ParseError},
+    registry::RuleId,
+    validator::{as_node, subset},
+};
+
+/// A rule declared in a registry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleSchema {
+    /// The name nodes use for the rule. It must not be the name or alias
+    /// of a built-in rule.
+    pub name: String,
+    /// The identifiers that stand for formulas in the patterns.
+    #[serde(default)]
+    pub metavariables: Vec<String>,
+    #[serde(default)]
+    pub premises: Vec<Sequent>,
+    pub conclusion: Sequent,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub side_conditions: Vec<SideCondition>,
+}
+
+/// A condition on an instance of a schema beyond matching its patterns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "condition", rename_all = "snake_case")]
+pub enum SideCondition {
+    /// The metavariable stands for a propositional variable or predicate.
+    Atomic { meta: String },
+    /// The rule is only sound classically.
+    Classical,
+}
+
+/// Why a registry's rule schemas are unusable.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SchemaError {
+    #[error("rule schema `{schema}` has the name of a built-in rule")]
+    BuiltinName { schema: String },
+    #[error("rule schema `{schema}` has a pattern that does not parse")]
+    Parse {
+        schema: String,
+        #[source]
+        source: ParseError,
+    },
+    #[error("rule schema `{schema}`: side condition on `{meta}`, which is not a metavariable")]
+    UnknownMetavariable { schema: String, meta: String },
+    #[error("rule schema `{schema}` is declared twice")]
+    Duplicate { schema: String },
+}
+
+/// Formulas assigned to metavariables.
+pub(crate) type Bindings = BTreeMap<String, Formula>;
+
+impl RuleSchema {
+    /// Check that the schema is usable: the name is free, the patterns
+    /// parse and the side conditions mention declared metavariables.
+    pub fn check_well_formed(&self) -> Result<(), SchemaError> {
+        if RuleId::from_name(&self.name).is_some() {
+            return Err(SchemaError::BuiltinName {
+                schema: self.name.clone(),
+            });
+        }
+        for s in self.premises.iter().chain([&self.conclusion]) {
+            resolve_sequent(s).map_err(|source| SchemaError::Parse {
+                schema: self.name.clone(),
+                source,
+            })?;
+        }
+        for c in &self.side_conditions {
+            match c {
+                SideCondition::Atomic { meta } if !self.metavariables.contains(meta) => {
+                    return Err(SchemaError::UnknownMetavariable {
+                        schema: self.name.clone(),
+                        meta: meta.clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Check one inference against the schema: does `concl` follow from
+    /// `prems` by some instance of it? On failure, returns the reason the
+    /// closest instance failed.
+    pub fn check(
+        &self,
+        calculus: Calculus,
+        contexts: Contexts,
+        concl: &Sequent,
+        prems: &[&Sequent],
+    ) -> Result<(), String> {
+        self.instance(calculus, contexts, concl, prems).map(|_| ())
+    }
+
+    /// [`RuleSchema::check`], returning the metavariable bindings of the
+    /// instance found.
+    pub(crate) fn instance(
+        &self,
+        calculus: Calculus,
+        contexts: Contexts,
+        concl: &Sequent,
+        prems: &[&Sequent],
+    ) -> Result<Bindings, String> {
+        if prems.len() != self.premises.len() {
+            return Err(format!(
+                "expected {} premise(s), found {}",
+                self.premises.len(),
+                prems.len()
+            ));
+        }
+        if contexts != Contexts::Set {
+            return Err(format!(
+                "{} is a rule schema; schemas read contexts as sets",
+                self.name
+            ));
+        }
+        if calculus != Calculus::Classical
+            && self.side_conditions.contains(&SideCondition::Classical)
+        {
+            return Err(format!("{} needs the classical calculus", self.name));
+        }
+        if calculus == Calculus::Intuitionistic {
+            if concl.succ.len() != 1 {
+                return Err(format!(
+                    "conclusion has {} formulas on the right; intuitionistic sequents have one",
+                    concl.succ.len()
+                ));
+            }
+            if let Some(n) = prems.iter().position(|p| p.succ.len() != 1) {
+                return Err(format!(
+                    "premise {} has {} formulas on the right; intuitionistic sequents have one",
+                    n + 1,
+                    prems[n].succ.len()
+                ));
+            }
+        }
+
+        let resolve = |s: &Sequent| resolve_sequent(s).map_err(|e| e.to_string());
+        let conclusion = resolve(&self.conclusion)?;
+        let premises = self
+            .premises
+            .iter()
+            .map(resolve)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Each pattern formula must be matched by a formula of the sequent
+        // it belongs to, except on the left of a premise, where weakening
+        // may have dropped it.
+        let mut goals = Vec::new();
+        for (pats, within, side) in [
+            (&conclusion.succ, &concl.succ, "right"),
+            (&conclusion.ctx, &concl.ctx, "left"),
+        ] {
+            for f in pats {
+                goals.push(Goal {
+                    pattern: f,
+                    within,
+                    optional: false,
+                    place: format!("conclusion has no formula matching {f} on the {side}"),
+                });
+            }
+        }
+        for (n, (pat, prem)) in premises.iter().zip(prems).enumerate() {
+            for f in &pat.succ {
+                goals.push(Goal {
+                    pattern: f,
+                    within: &prem.succ,
+                    optional: false,
+                    place: format!("premise {} has no formula matching {f} on the right", n + 1),
+                });
+            }
+            for f in &pat.ctx {
+                goals.push(Goal {
+                    pattern: f,
+                    within: &prem.ctx,
+                    optional: true,
+                    place: format!("premise {} has no formula matching {f} on the left", n + 1),
+                });
+            }
+        }
+
+        let mut search = Search {
+            schema: self,
+            concl,
+            prems,
+            premises: &premises,
+            goals: &goals,
+            closest: (0, String::new()),
+        };
+        let mut bindings = Bindings::new();
+        if search.solve(0, &mut bindings) {
+            Ok(bindings)
+        } else {
+            Err(search.closest.1)
+        }
+    }
+
+    fn is_meta(&self, name: &str) -> bool {
+        self.metavariables.iter().any(|m| m == name)
+    }
+
+    /// The pattern `pat` with its metavariables replaced by their bindings.
+    pub(crate) fn instantiate(&self, pat: &Formula, b: &Bindings) -> Formula {
+        instantiate(self, pat, b)
+    }
+
+    /// The metavariables of the schema occurring in `pat`.
+    pub(crate) fn metas_in<'a>(&self, pat: &'a Formula) -> HashSet<&'a str> {
+        metas(self, pat)
+    }
+}
+
+/// A pattern formula to find in one side of a sequent.
+struct Goal<'a> {
+    pattern: &'a Formula,
+    within: &'a [Formula],
+    optional: bool,
+    /// The reason to give when nothing matches.
+    place: String,
+}
+
+/// A backtracking search for bindings under which every goal is met and
+/// the instance checks.
+struct Search<'a> {
+    schema: &'a RuleSchema,
+    concl: &'a Sequent,
+    prems: &'a [&'a Sequent],
+    premises: &'a [Sequent],
+    goals: &'a [Goal<'a>],
+    /// The deepest goal reached and why it failed there.
+    closest: (usize, String),
+}
+
+impl Search<'_> {
+    fn solve(&mut self, i: usize, b: &mut Bindings) -> bool {
+        let Some(goal) = self.goals.get(i) else {
+            return match self.instance(b) {
+                Ok(()) => true,
+                Err(reason) => self.fail(i, reason),
+            };
+        };
+        for f in goal.within {
+            let mut next = b.clone();
+            if matches(self.schema, goal.pattern, f, &mut next) && self.solve(i + 1, &mut next) {
+                *b = next;
+                return true;
+            }
+        }
+        let bound = metas(self.schema, goal.pattern)
+            .iter()
+            .all(|m| b.contains_key(*m));
+        if goal.optional && bound {
+            return self.solve(i + 1, b);
+        }
+        self.fail(i, goal.place.clone())
+    }
+
+    fn fail(&mut self, depth: usize, reason: String) -> bool {
+        if self.closest.1.is_empty() || depth > self.closest.0 {
+            self.closest = (depth, reason);
+        }
+        false
+    }
+
+    /// With every pattern formula placed, check the premises' contexts and
+    /// the side conditions.
+    fn instance(&self, b: &Bindings) -> Result<(), String> {
+        for (n, (pat, prem)) in self.premises.iter().zip(self.prems).enumerate() {
+            let side = |shown: &[Formula], extra: &[Formula]| {
+                let mut allowed = shown.to_vec();
+                allowed.extend(extra.iter().map(|f| instantiate(self.schema, f, b)));
+                allowed
+            };
+            if !subset(&prem.ctx, &side(&self.concl.ctx, &pat.ctx)) {
+                return Err(format!(
+                    "premise {} uses hypotheses outside the conclusion and the schema",
+                    n + 1
+                ));
+            }
+            if !subset(&prem.succ, &side(&self.concl.succ, &pat.succ)) {
+                return Err(format!(
+                    "premise {} proves formulas outside the conclusion and the schema",
+                    n + 1
+                ));
+            }
+        }
+        for c in &self.schema.side_conditions {
+            if let SideCondition::Atomic { meta } = c {
+                match b.get(meta) {
+                    Some(f) if is_atomic(f) => {}
+                    Some(f) => return Err(format!("{meta} must be atomic, found {f}")),
+                    None => return Err(format!("{meta} is not bound by the schema's patterns")),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Match `f` against the pattern `pat`, extending `b`.
+fn matches(s: &RuleSchema, pat: &Formula, f: &Formula, b: &mut Bindings) -> bool {
+    use FormulaNode as N;
+    let (Some(p), Some(g)) = (as_node(pat), as_node(f)) else {
+        return pat.alpha_eq(f);
+    };
+    match (p, g) {
+        (N::Var(m), _) if s.is_meta(m) => match b.get(m) {
+            Some(bound) => bound.alpha_eq(f),
+            None => {
+                b.insert(m.clone(), f.clone());
+                true
+            }
+        },
+        (N::And(a, c), N::And(x, y))
+        | (N::Or(a, c), N::Or(x, y))
+        | (N::Imp(a, c), N::Imp(x, y))
+        | (N::Iff(a, c), N::Iff(x, y)) => matches(s, a, x, b) && matches(s, c, y, b),
+        (N::Not(a), N::Not(x)) => matches(s, a, x, b),
+        (N::Forall(v, a), N::Forall(w, x)) | (N::Exists(v, a), N::Exists(w, x)) => {
+            v == w && matches(s, a, x, b)
+        }
+        _ => pat.alpha_eq(f),
+    }
+}
+
+/// The pattern `pat` with its metavariables replaced by their bindings.
+fn instantiate(s: &RuleSchema, pat: &Formula, b: &Bindings) -> Formula {
+    use FormulaNode as N;
+    let Formula::Node(node) = pat else {
+        return pat.clone();
+    };
+    let bx = |g: &Formula| Box::new(instantiate(s, g, b));
+    match node {
+        N::Var(m) if s.is_meta(m) => b.get(m).cloned().unwrap_or_else(|| pat.clone()),
+        N::Var(_) | N::Bot | N::Top | N::Pred { .. } => pat.clone(),
+        N::And(x, y) => Formula::Node(N::And(bx(x), bx(y))),
+        N::Or(x, y) => Formula::Node(N::Or(bx(x), bx(y))),
+        N::Imp(x, y) => Formula::Node(N::Imp(bx(x), bx(y))),
+        N::Iff(x, y) => Formula::Node(N::Iff(bx(x), bx(y))),
+        N::Not(x) => Formula::Node(N::Not(bx(x))),
+        N::Forall(v, x) => Formula::Node(N::Forall(v.clone(), bx(x))),
+        N::Exists(v, x) => Formula::Node(N::Exists(v.clone(), bx(x))),
+    }
+}
+
+/// The metavariables occurring in `pat`.
+fn metas<'a>(s: &RuleSchema, pat: &'a Formula) -> HashSet<&'a str> {
+    use FormulaNode as N;
+    let mut out = HashSet::new();
+    let mut stack = vec![pat];
+    while let Some(f) = stack.pop() {
+        match as_node(f) {
+            Some(N::Var(m)) if s.is_meta(m) => {
+                out.insert(m.as_str());
+            }
+            Some(N::And(x, y) | N::Or(x, y) | N::Imp(x, y) | N::Iff(x, y)) => {
+                stack.extend([&**x, &**y]);
+            }
+            Some(N::Not(x) | N::Forall(_, x) | N::Exists(_, x)) => stack.push(x),
+            _ => {}
+        }
+    }
+    out
+}
+
+pub(crate) fn is_atomic(f: &Formula) -> bool {
+    matches!(
+        as_node(f),
+        Some(FormulaNode::Var(_) | FormulaNode::Pred { .. })
+    )
+}
+
+/// Check a list of schemas as a registry declares them: each well-formed,
+/// with distinct names.
+pub(crate) fn check_schemas(schemas: &[RuleSchema]) -> Result<(), SchemaError> {
+    let mut names = HashSet::new();
+    for s in schemas {
+        s.check_well_formed()?;
+        if !names.insert(s.name.as_str()) {
+            return Err(SchemaError::Duplicate {
+                schema: s.name.clone(),
+            });
+        }
+    }
+    Ok(())
+}
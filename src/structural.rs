@@ -0,0 +1,89 @@
+// src/structural.rs
+//! Admissibility of the structural rules.
+//!
+//! With set contexts every logical rule tolerates extra formulas, so a
+//! structural inference can be dropped: its node keeps its sequent and
+//! takes over the rule, premises and term of the first non-structural
+//! inference above it. When contexts count formulas, logical rules need
+//! their contexts exactly and the structural rules are not admissible.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    ast::{Contexts, Proof, ProofNode, Sequent},
+    parse::resolve_proof,
+    registry::RuleId,
+    validator::{check_rule, validate_local_wf, ValidationError},
+};
+
+/// Remove every inference by one of `rules` that is structural.
+///
+/// The proof must pass [`validate_local_wf`]; the output uses the
+/// structured form of every formula and drops nodes that are no longer
+/// reachable from the root. An inference stays when dropping it would break
+/// the rule above, as when a weakened formula mentions an eigenvariable.
+/// Only proofs with set contexts are rewritten; others are returned
+/// unchanged.
+pub fn eliminate_structural(p: &Proof, rules: &HashSet<RuleId>) -> Result<Proof, ValidationError> {
+    validate_local_wf(p)?;
+    if p.contexts != Contexts::Set {
+        return Ok(p.clone());
+    }
+    let resolved = resolve_proof(p)?;
+    let by_id: HashMap<&str, &ProofNode> =
+        resolved.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+    let kind = |n: &ProofNode| RuleId::from_name(&n.rule).expect("validated");
+
+    let mut nodes = Vec::with_capacity(resolved.nodes.len());
+    for n in &resolved.nodes {
+        if !(kind(n).is_structural() && rules.contains(&kind(n))) {
+            nodes.push(n.clone());
+            continue;
+        }
+        let mut above = by_id[n.premises[0].as_str()];
+        while kind(above).is_structural() {
+            above = by_id[above.premises[0].as_str()];
+        }
+        let prems: Vec<&Sequent> = above
+            .premises
+            .iter()
+            .map(|id| &by_id[id.as_str()].sequent)
+            .collect();
+        let fits = check_rule(
+            resolved.calculus,
+            resolved.contexts,
+            kind(above),
+            &n.sequent,
+            &prems,
+            above.term.as_ref(),
+        )
+        .is_ok();
+        nodes.push(if fits {
+            ProofNode {
+                id: n.id.clone(),
+                sequent: n.sequent.clone(),
+                ..above.clone()
+            }
+        } else {
+            n.clone()
+        });
+    }
+
+    let reachable = reachable(&nodes, &resolved.root);
+    nodes.retain(|n| reachable.contains(&n.id));
+    Ok(Proof { nodes, ..resolved })
+}
+
+fn reachable(nodes: &[ProofNode], root: &str) -> HashSet<String> {
+    let by_id: HashMap<&str, &ProofNode> = nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+    let mut seen = HashSet::new();
+    let mut stack = vec![root];
+    while let Some(id) = stack.pop() {
+        if seen.insert(id.to_string()) {
+            if let Some(n) = by_id.get(id) {
+                stack.extend(n.premises.iter().map(String::as_str));
+            }
+        }
+    }
+    seen
+}
@@ -1,48 +1,539 @@
 // src/transport.rs
-use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use thiserror::Error;
 
 use crate::{
-    ast::Proof,
-    cutelim::cut_eliminate_all,
+    ast::{Calculus, Contexts, Formula, Proof, Sequent},
+    cert::{Certificate, DirectLoss, Hop, RewriteStep},
+    cutelim::{cut_eliminate_all, cut_eliminate_unless},
+    derive::{expand, same_rule, ExpandError},
+    eta::{eta_expand, EtaError},
     frag::fragility_score,
+    parse::{resolve_proof, resolve_sequent},
     registry::{Registry, RuleId},
-    validator::validate_local_wf,
+    restrict::{broken, Restriction},
+    structural::eliminate_structural,
+    unfold::{unfold, Connective},
+    validator::{validate_at, validate_with, ValidationError},
 };
 
+/// Why a proof could not be transported.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TransportError {
+    /// The proof was not well-formed to begin with.
+    #[error("input proof is not well-formed")]
+    InvalidInput(#[source] ValidationError),
+    /// A rewrite produced an ill-formed proof.
+    #[error("transported proof is not well-formed at t={t}")]
+    InvalidOutput {
+        t: u64,
+        #[source]
+        source: ValidationError,
+    },
+    /// The rewrites changed what the proof proves, beyond unfolding.
+    #[error("transport changed the end sequent from {before} to {after}")]
+    EndSequentChanged {
+        before: Box<Sequent>,
+        after: Box<Sequent>,
+    },
+    /// Rules disabled at the target time are used, and nothing can remove
+    /// them; `uses` lists every inference by one of them.
+    #[error("no strategy removes the rules disabled at t={t}: {}", list(.uses))]
+    NoStrategy { t: u64, uses: Vec<RuleUse> },
+    /// Inferences break restrictions the target time puts on their rules,
+    /// and nothing can repair them; `uses` lists every one.
+    #[error("no strategy repairs the inferences that break restrictions at t={t}: {}", list(.uses))]
+    NoRepair { t: u64, uses: Vec<RuleUse> },
+    /// A registry derivation could not replace a rule.
+    #[error("cannot expand a derived rule")]
+    Expansion(#[source] ExpandError),
+    /// An identity axiom on a compound formula could not be expanded.
+    #[error("cannot eta-expand an identity axiom")]
+    EtaExpansion(#[source] EtaError),
+    /// A rule disabled at the target time is still used after rewriting.
+    #[error("node {node} uses {rule}, which is disabled at t={t}")]
+    RuleDisabled { node: String, rule: RuleId, t: u64 },
+    /// A rule schema not enabled at the target time is still used.
+    #[error("node {node} uses rule schema {rule}, which is disabled at t={t}")]
+    SchemaDisabled { node: String, rule: String, t: u64 },
+    /// An inference still breaks a restriction on its rule after rewriting.
+    #[error("node {node} breaks the {restriction} restriction on {rule} at t={t}")]
+    RestrictionBroken {
+        node: String,
+        rule: RuleId,
+        restriction: Restriction,
+        t: u64,
+    },
+}
+
+/// An inference, named by its node and rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleUse {
+    pub node: String,
+    pub rule: String,
+}
+
+impl fmt::Display for RuleUse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.node, self.rule)
+    }
+}
+
+fn list(uses: &[RuleUse]) -> String {
+    let uses: Vec<String> = uses.iter().map(RuleUse::to_string).collect();
+    uses.join(", ")
+}
+
+/// How transport removes a disabled rule from a proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Replace each use by the registry's derivation of the rule.
+    Expansion,
+    /// Unfold the connective the rule acts on.
+    Unfold(Connective),
+    /// Drop the structural inferences.
+    StructuralElimination,
+    /// Eliminate the cuts.
+    CutElimination,
+    /// Eta-expand the identity axioms.
+    EtaExpansion,
+}
+
+/// How `rule` can be removed from a proof in `calculus` with `contexts`
+/// transported to time `t`, if it can. A derivation in the registry takes
+/// precedence over the built-in strategies. Every strategy rewrites
+/// inferences as if contexts were sets, so proofs whose contexts count
+/// formulas have none; cut elimination further needs the intuitionistic
+/// calculus.
+pub fn strategy(
+    reg: &Registry,
+    rule: &str,
+    t: u64,
+    calculus: Calculus,
+    contexts: Contexts,
+) -> Option<Strategy> {
+    if contexts != Contexts::Set {
+        return None;
+    }
+    if reg
+        .derivations_at(t)
+        .iter()
+        .any(|d| same_rule(&d.rule, rule))
+    {
+        return Some(Strategy::Expansion);
+    }
+    let rule = RuleId::from_name(rule)?;
+    match rule {
+        RuleId::Cut => cut_elimination(calculus),
+        _ if rule.is_structural() => Some(Strategy::StructuralElimination),
+        _ => [Connective::Not, Connective::Iff]
+            .into_iter()
+            .find(|c| c.rules().contains(&rule))
+            .map(Strategy::Unfold),
+    }
+}
+
+/// How transport repairs inferences by `rule` that break `restriction`,
+/// in a proof in `calculus` with `contexts`, if it can.
+pub fn restriction_strategy(
+    rule: RuleId,
+    restriction: Restriction,
+    calculus: Calculus,
+    contexts: Contexts,
+) -> Option<Strategy> {
+    if contexts != Contexts::Set {
+        return None;
+    }
+    match (rule, restriction) {
+        (RuleId::Cut, Restriction::Atomic | Restriction::Analytic) => cut_elimination(calculus),
+        (RuleId::Id, Restriction::Atomic) => Some(Strategy::EtaExpansion),
+        _ => None,
+    }
+}
+
+/// Cut elimination, where [`cut_eliminate_all`] reduces cuts.
+fn cut_elimination(calculus: Calculus) -> Option<Strategy> {
+    (calculus == Calculus::Intuitionistic).then_some(Strategy::CutElimination)
+}
+
 /// Transport a proof between registry times.
 ///
-/// Phase‑1 behavior:
-/// 1) validate input
-/// 2) if target time disables Cut, eliminate all cuts
-/// 3) validate output
-pub fn transport(proof: &Proof, reg: &Registry, _from: u64, to: u64) -> Result<Proof> {
-    // Keep strict lints happy; Phase‑1 semantics don’t depend on `from`.
-    let _ = _from;
+/// Transport walks from `from` to `to` one hop at a time, stopping at every
+/// time in between where the registry changes, so each slice's rules and
+/// derivations apply in order. Each hop does the following:
+/// 1) validate input, accepting the registry's rule schemas, and check that
+///    every rule it uses that is disabled at the target time has a
+///    [`strategy`], and every inference that breaks a restriction there a
+///    [`restriction_strategy`]
+/// 2) replace each rule disabled at the target time that the registry
+///    derives there by its derivation, in the order declared
+/// 3) if target time disables a rule of `¬` or `⇔` that the proof uses,
+///    unfold that connective
+/// 4) if target time disables a structural rule that the proof uses, drop
+///    its inferences
+/// 5) if target time disables Cut, eliminate all cuts; if it restricts
+///    Cut, eliminate the cuts that break the restrictions; if it restricts
+///    Id to atoms, eta-expand the identity axioms on compound formulas with
+///    the rules enabled there
+/// 6) validate output
+/// 7) check that the end sequent is unchanged, up to alpha-equivalence and
+///    the unfolding in step 3
+/// 8) check that every rule used is enabled at the target time, within
+///    its restrictions there
+/// 9) write each rule under the name the target slice gives it
+pub fn transport(
+    proof: &Proof,
+    reg: &Registry,
+    from: u64,
+    to: u64,
+) -> Result<Proof, TransportError> {
+    transport_with_certificate(proof, reg, from, to).map(|(p, _)| p)
+}
+
+/// [`transport`], also returning a [`Certificate`] that records what was
+/// done, hop by hop, and can be re-checked against the proofs later.
+///
+/// When the walk has several hops, the certificate also notes whether
+/// going straight to `to` would lose something the walk keeps: see
+/// [`DirectLoss`].
+pub fn transport_with_certificate(
+    proof: &Proof,
+    reg: &Registry,
+    from: u64,
+    to: u64,
+) -> Result<(Proof, Certificate), TransportError> {
+    let mut p = proof.clone();
+    let mut hops = Vec::new();
+    let mut at = from;
+    for t in hop_times(reg, from, to) {
+        let (q, steps) = hop(&p, reg, t)?;
+        hops.push(Hop {
+            from: at,
+            to: t,
+            steps,
+        });
+        (p, at) = (q, t);
+    }
+
+    let direct_loss = match hops.as_slice() {
+        [.., last] if hops.len() > 1 => match hop(proof, reg, to) {
+            Err(e) => Some(DirectLoss::Fails {
+                reason: error_chain(&e),
+            }),
+            Ok((direct, _)) => hops
+                .iter()
+                .map(|h| h.to)
+                .filter(|&t| t != last.to)
+                .find(|&t| validate_at(&p, reg, t).is_ok() && validate_at(&direct, reg, t).is_err())
+                .map(|t| DirectLoss::InvalidAt { t }),
+        },
+        _ => None,
+    };
+
+    let cert = Certificate::new(proof, &p, reg, from, to, hops, direct_loss);
+    Ok((p, cert))
+}
+
+/// The times transport from `from` to `to` stops at: each time in between
+/// at which the registry changes, then `to`.
+fn hop_times(reg: &Registry, from: u64, to: u64) -> Vec<u64> {
+    let mut times: Vec<u64> = reg
+        .change_times()
+        .into_iter()
+        .filter(|&t| from < t && t < to)
+        .collect();
+    times.push(to);
+    times
+}
 
+/// One hop of transport, to the rules of time `to`, with the steps 1)–8)
+/// of [`transport`].
+fn hop(
+    proof: &Proof,
+    reg: &Registry,
+    to: u64,
+) -> Result<(Proof, Vec<RewriteStep>), TransportError> {
     // What is enabled at the target time?
     let enabled_to = reg.enabled_at(to);
+    let mut steps = Vec::new();
 
     // Clone to avoid mutating the caller’s proof.
     let mut p = proof.clone();
 
-    // 1) Validate starting proof
-    validate_local_wf(&p)?;
+    // 1) Validate starting proof, reading rules a slice renamed under our
+    //    names
+    validate_with(&p, reg).map_err(TransportError::InvalidInput)?;
+    for n in &mut p.nodes {
+        if RuleId::from_name(&n.rule).is_none() {
+            if let Some(rule) = reg.rule_id(&n.rule, None) {
+                n.rule = rule.name().to_string();
+            }
+        }
+    }
+    let stuck: Vec<RuleUse> = p
+        .nodes
+        .iter()
+        .zip(&proof.nodes)
+        .filter(|(n, _)| {
+            !reg.is_enabled(&n.rule, to)
+                && strategy(reg, &n.rule, to, p.calculus, p.contexts).is_none()
+        })
+        .map(|(_, written)| RuleUse {
+            node: written.id.clone(),
+            rule: written.rule.clone(),
+        })
+        .collect();
+    if !stuck.is_empty() {
+        return Err(TransportError::NoStrategy { t: to, uses: stuck });
+    }
+    let resolved = resolve_proof(&p).map_err(|e| TransportError::InvalidInput(e.into()))?;
+    let unrepaired: Vec<RuleUse> = broken(&resolved, reg, to)
+        .into_iter()
+        .filter(|(_, rule, r)| restriction_strategy(*rule, *r, p.calculus, p.contexts).is_none())
+        .map(|(n, ..)| RuleUse {
+            node: n.id.clone(),
+            rule: n.rule.clone(),
+        })
+        .collect();
+    if !unrepaired.is_empty() {
+        return Err(TransportError::NoRepair {
+            t: to,
+            uses: unrepaired,
+        });
+    }
+
+    // 2) Expand derived rules that are going away
+    for d in reg.derivations_at(to) {
+        let expanded = p
+            .nodes
+            .iter()
+            .filter(|n| same_rule(&n.rule, &d.rule))
+            .count();
+        if expanded > 0 && !reg.is_enabled(&d.rule, to) {
+            p = expand(&p, d, reg).map_err(TransportError::Expansion)?;
+            steps.push(RewriteStep::Expansion {
+                rule: d.rule.clone(),
+                expanded,
+            });
+        }
+    }
+
+    // 3) Unfold `¬` and `⇔` where their rules are going away
+    for c in [Connective::Not, Connective::Iff] {
+        let rewritten: usize = c.rules().into_iter().map(|r| count_rule(&p, r)).sum();
+        if rewritten > 0 && c.rules().iter().any(|r| !enabled_to.contains(r)) {
+            p = unfold(&p, c).map_err(|source| TransportError::InvalidOutput { t: to, source })?;
+            steps.push(RewriteStep::Unfold {
+                connective: c,
+                rewritten,
+            });
+        }
+    }
+
+    // 4) Structural rules are admissible: drop the disabled ones
+    let disabled: HashSet<RuleId> = RuleId::ALL
+        .into_iter()
+        .filter(|r| r.is_structural() && !enabled_to.contains(r))
+        .collect();
+    if disabled.iter().any(|r| count_rule(&p, *r) > 0) {
+        let structural = |p: &Proof| -> usize {
+            RuleId::ALL
+                .into_iter()
+                .filter(|r| r.is_structural())
+                .map(|r| count_rule(p, r))
+                .sum()
+        };
+        let before = structural(&p);
+        p = eliminate_structural(&p, &disabled)
+            .map_err(|source| TransportError::InvalidOutput { t: to, source })?;
+        steps.push(RewriteStep::StructuralElimination {
+            removed: before - structural(&p),
+        });
+    }
 
-    // 2) Apply registry‑aware transform: if Cut is disabled at the target, eliminate all cuts
-    if !enabled_to.contains(&RuleId::Cut) {
-        p = cut_eliminate_all(&p);
+    // 5) Apply registry‑aware transform: if Cut is disabled at the target, eliminate all cuts
+    let cuts = count_rule(&p, RuleId::Cut);
+    if cuts > 0 && !enabled_to.contains(&RuleId::Cut) {
+        p = cut_eliminate_all(&p)
+            .map_err(|source| TransportError::InvalidOutput { t: to, source })?;
+        steps.push(RewriteStep::CutElimination {
+            removed: cuts - count_rule(&p, RuleId::Cut),
+        });
     }
 
-    // 3) Validate resulting proof
-    validate_local_wf(&p)?;
+    // ... or only those outside its restrictions
+    let restrictions = reg.restrictions_at(RuleId::Cut, to);
+    if cuts > 0 && enabled_to.contains(&RuleId::Cut) && !restrictions.is_empty() {
+        let end = end_sequent(&p)?;
+        let allowed = |a: &Formula| restrictions.iter().all(|r| r.allows(a, &end));
+        let resolved = resolve_proof(&p).map_err(|source| TransportError::InvalidOutput {
+            t: to,
+            source: source.into(),
+        })?;
+        let removed = broken(&resolved, reg, to)
+            .iter()
+            .filter(|(_, rule, _)| *rule == RuleId::Cut)
+            .map(|(n, ..)| n.id.as_str())
+            .collect::<HashSet<_>>()
+            .len();
+        if removed > 0 {
+            p = cut_eliminate_unless(&p, &allowed)
+                .map_err(|source| TransportError::InvalidOutput { t: to, source })?;
+            steps.push(RewriteStep::RestrictedCutElimination { removed });
+        }
+    }
+
+    // ... then take apart the identity axioms on compound formulas
+    if reg
+        .restrictions_at(RuleId::Id, to)
+        .contains(&Restriction::Atomic)
+    {
+        let resolved = resolve_proof(&p).map_err(|source| TransportError::InvalidOutput {
+            t: to,
+            source: source.into(),
+        })?;
+        let expanded = broken(&resolved, reg, to)
+            .iter()
+            .filter(|(_, rule, r)| *rule == RuleId::Id && *r == Restriction::Atomic)
+            .count();
+        if expanded > 0 {
+            p = eta_expand(&p, &enabled_to).map_err(TransportError::EtaExpansion)?;
+            steps.push(RewriteStep::EtaExpansion { expanded });
+        }
+    }
+
+    // 6) Validate resulting proof
+    validate_with(&p, reg).map_err(|source| TransportError::InvalidOutput { t: to, source })?;
+
+    // 7) Same theorem, same hypotheses
+    let before = steps
+        .iter()
+        .fold(end_sequent(proof)?, |s, step| step.rewrite_end_sequent(&s));
+    let after = end_sequent(&p)?;
+    if !before.alpha_eq(&after) {
+        return Err(TransportError::EndSequentChanged {
+            before: Box::new(before),
+            after: Box::new(after),
+        });
+    }
+
+    // 8) Nothing disabled may remain
+    let schemas_to = reg.schemas_at(to);
+    for n in &p.nodes {
+        match RuleId::from_name(&n.rule) {
+            Some(rule) if !enabled_to.contains(&rule) => {
+                return Err(TransportError::RuleDisabled {
+                    node: n.id.clone(),
+                    rule,
+                    t: to,
+                });
+            }
+            None if !schemas_to.contains(n.rule.as_str()) => {
+                return Err(TransportError::SchemaDisabled {
+                    node: n.id.clone(),
+                    rule: n.rule.clone(),
+                    t: to,
+                });
+            }
+            _ => {}
+        }
+    }
 
-    Ok(p)
+    let resolved = resolve_proof(&p).map_err(|source| TransportError::InvalidOutput {
+        t: to,
+        source: source.into(),
+    })?;
+    if let Some((n, rule, restriction)) = broken(&resolved, reg, to).into_iter().next() {
+        return Err(TransportError::RestrictionBroken {
+            node: n.id.clone(),
+            rule,
+            restriction,
+            t: to,
+        });
+    }
+
+    // 9) Use the target kernel's names for its rules
+    let written: HashMap<&str, &str> = proof
+        .nodes
+        .iter()
+        .map(|n| (n.id.as_str(), n.rule.as_str()))
+        .collect();
+    let mut renamed = 0;
+    for n in &mut p.nodes {
+        let Some(rule) = RuleId::from_name(&n.rule) else {
+            continue;
+        };
+        let name = reg.name_at(rule, to);
+        if name != rule.name() {
+            n.rule = name.to_string();
+        }
+        let before = written.get(n.id.as_str());
+        if before.is_some_and(|b| *b != n.rule && reg.rule_id(b, None) == Some(rule)) {
+            renamed += 1;
+        }
+    }
+    if renamed > 0 {
+        steps.push(RewriteStep::Rename { renamed });
+    }
+
+    Ok((p, steps))
+}
+
+/// `e` and its sources, separated by colons.
+pub(crate) fn error_chain(e: &dyn std::error::Error) -> String {
+    let mut s = e.to_string();
+    let mut source = e.source();
+    while let Some(cause) = source {
+        s.push_str(&format!(": {cause}"));
+        source = cause.source();
+    }
+    s
+}
+
+/// The root sequent with its text parsed.
+fn end_sequent(p: &Proof) -> Result<Sequent, TransportError> {
+    let root = p.root_node().expect("validated");
+    resolve_sequent(&root.sequent).map_err(|e| TransportError::InvalidInput(e.into()))
+}
+
+fn count_rule(proof: &Proof, rule: RuleId) -> usize {
+    proof
+        .nodes
+        .iter()
+        .filter(|n| RuleId::from_name(&n.rule) == Some(rule))
+        .count()
 }
 
 /// Convenience helper for tests/metrics: change in fragility across a transport.
-pub fn fragility_delta(proof: &Proof, reg: &Registry, from: u64, to: u64) -> Result<i64> {
+pub fn fragility_delta(
+    proof: &Proof,
+    reg: &Registry,
+    from: u64,
+    to: u64,
+) -> Result<i64, TransportError> {
     let before = fragility_score(proof) as i64;
     let after_proof = transport(proof, reg, from, to)?;
     let after = fragility_score(&after_proof) as i64;
     Ok(after - before)
 }
+
+/// The rules a proof uses, by registry id. Rule schemas and unknown rule
+/// names are skipped; `validate_local_wf` reports the unknown ones.
+pub fn rules_used(proof: &Proof) -> HashSet<RuleId> {
+    proof
+        .nodes
+        .iter()
+        .filter_map(|n| RuleId::from_name(&n.rule))
+        .collect()
+}
+
+/// [`rules_used`], also reading the names the slice at `t` gives rules.
+pub fn rules_used_at(proof: &Proof, reg: &Registry, t: u64) -> HashSet<RuleId> {
+    proof
+        .nodes
+        .iter()
+        .filter_map(|n| reg.rule_id(&n.rule, Some(t)))
+        .collect()
+}
@@ -0,0 +1,209 @@
+// src/unfold.rs
+//! Unfolding `¬` and `⇔` into the other connectives.
+//!
+//! `¬A` is read as `A ⇒ ⊥` and `A ⇔ B` as `(A ⇒ B) ∧ (B ⇒ A)`. Unfolding a
+//! connective rewrites every formula of the proof and replaces its rules by
+//! derivations in the remaining ones:
+//!
+//! ```text
+//! NotR ↦ ImpR
+//! NotL ↦ ImpL, with a BotI node for Γ, ⊥ ⊢ Δ as second premise
+//! IffR ↦ AndR over two ImpR nodes
+//! IffL ↦ AndL1 over AndL2
+//! ```
+
+use std::collections::HashSet;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ast::{Calculus, Formula, FormulaNode, Proof, ProofNode, Sequent},
+    parse::resolve_proof,
+    registry::RuleId,
+    validator::{extend, principal_formula, validate_local_wf, ValidationError},
+};
+
+/// A connective defined in terms of the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Connective {
+    Not,
+    Iff,
+}
+
+impl Connective {
+    /// The rules acting on the connective, which unfolding removes.
+    pub fn rules(self) -> [RuleId; 2] {
+        match self {
+            Connective::Not => [RuleId::NotL, RuleId::NotR],
+            Connective::Iff => [RuleId::IffL, RuleId::IffR],
+        }
+    }
+}
+
+impl fmt::Display for Connective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Connective::Not => "¬",
+            Connective::Iff => "⇔",
+        })
+    }
+}
+
+/// Unfold `c` everywhere in `f`. Formulas written as text are left alone;
+/// resolve them first.
+pub fn unfold_formula(f: &Formula, c: Connective) -> Formula {
+    let Formula::Node(node) = f else {
+        return f.clone();
+    };
+    let bx = |g: &Formula| Box::new(unfold_formula(g, c));
+    Formula::Node(match node {
+        FormulaNode::Var(_) | FormulaNode::Bot | FormulaNode::Top | FormulaNode::Pred { .. } => {
+            node.clone()
+        }
+        FormulaNode::And(a, b) => FormulaNode::And(bx(a), bx(b)),
+        FormulaNode::Or(a, b) => FormulaNode::Or(bx(a), bx(b)),
+        FormulaNode::Imp(a, b) => FormulaNode::Imp(bx(a), bx(b)),
+        FormulaNode::Not(a) if c == Connective::Not => {
+            FormulaNode::Imp(bx(a), Box::new(Formula::Node(FormulaNode::Bot)))
+        }
+        FormulaNode::Not(a) => FormulaNode::Not(bx(a)),
+        FormulaNode::Iff(a, b) if c == Connective::Iff => {
+            let (a, b) = (unfold_formula(a, c), unfold_formula(b, c));
+            FormulaNode::And(Box::new(imp(&a, &b)), Box::new(imp(&b, &a)))
+        }
+        FormulaNode::Iff(a, b) => FormulaNode::Iff(bx(a), bx(b)),
+        FormulaNode::Forall(x, a) => FormulaNode::Forall(x.clone(), bx(a)),
+        FormulaNode::Exists(x, a) => FormulaNode::Exists(x.clone(), bx(a)),
+    })
+}
+
+/// Unfold `c` in every formula of `s`.
+pub fn unfold_sequent(s: &Sequent, c: Connective) -> Sequent {
+    Sequent {
+        ctx: s.ctx.iter().map(|f| unfold_formula(f, c)).collect(),
+        succ: s.succ.iter().map(|f| unfold_formula(f, c)).collect(),
+    }
+}
+
+/// Unfold `c` in a proof, so that it no longer uses the rules of `c`.
+///
+/// The proof must pass [`validate_local_wf`]; the output uses the
+/// structured form of every formula. Node ids are kept, and the extra nodes
+/// the rewrites need are named after the node they come from.
+pub fn unfold(p: &Proof, c: Connective) -> Result<Proof, ValidationError> {
+    validate_local_wf(p)?;
+    let resolved = resolve_proof(p)?;
+    let mut used: HashSet<String> = resolved.nodes.iter().map(|n| n.id.clone()).collect();
+    let mut fresh = |base: &str| {
+        let mut id = base.to_string();
+        let mut k = 1;
+        while used.contains(&id) {
+            id = format!("{base}_{k}");
+            k += 1;
+        }
+        used.insert(id.clone());
+        id
+    };
+
+    let mut nodes = Vec::with_capacity(resolved.nodes.len());
+    for n in &resolved.nodes {
+        let sequent = unfold_sequent(&n.sequent, c);
+        let rule = RuleId::from_name(&n.rule).expect("validated");
+        let prems: Vec<&Sequent> = n
+            .premises
+            .iter()
+            .map(|id| &resolved.node(id).expect("validated").sequent)
+            .collect();
+        let node = |id: String, rule: RuleId, sequent: Sequent, premises: Vec<String>| ProofNode {
+            id,
+            rule: rule.name().to_string(),
+            sequent,
+            premises,
+            term: None,
+        };
+
+        let principal = || match principal_formula(rule, &n.sequent, &prems, n.term.as_ref()) {
+            Some(Formula::Node(FormulaNode::Iff(a, b))) => {
+                (unfold_formula(a, c), unfold_formula(b, c))
+            }
+            _ => unreachable!("validated {rule} has a biconditional as principal formula"),
+        };
+        match (c, rule) {
+            (Connective::Not, RuleId::NotR) => {
+                nodes.push(node(
+                    n.id.clone(),
+                    RuleId::ImpR,
+                    sequent,
+                    n.premises.clone(),
+                ));
+            }
+            (Connective::Not, RuleId::NotL) => {
+                let bot = Formula::Node(FormulaNode::Bot);
+                let bot_i = node(
+                    fresh(&format!("{}_bot", n.id)),
+                    RuleId::BotI,
+                    Sequent {
+                        ctx: extend(&sequent.ctx, [&bot]),
+                        succ: sequent.succ.clone(),
+                    },
+                    Vec::new(),
+                );
+                let premises = vec![n.premises[0].clone(), bot_i.id.clone()];
+                nodes.push(node(n.id.clone(), RuleId::ImpL, sequent, premises));
+                nodes.push(bot_i);
+            }
+            (Connective::Iff, RuleId::IffR) => {
+                let (a, b) = principal();
+                let side = |f: Formula| Sequent {
+                    ctx: sequent.ctx.clone(),
+                    succ: match p.calculus {
+                        Calculus::Intuitionistic => vec![f],
+                        Calculus::Classical => extend(&sequent.succ, [&f]),
+                    },
+                };
+                let to = node(
+                    fresh(&format!("{}_imp1", n.id)),
+                    RuleId::ImpR,
+                    side(imp(&a, &b)),
+                    vec![n.premises[0].clone()],
+                );
+                let from = node(
+                    fresh(&format!("{}_imp2", n.id)),
+                    RuleId::ImpR,
+                    side(imp(&b, &a)),
+                    vec![n.premises[1].clone()],
+                );
+                let premises = vec![to.id.clone(), from.id.clone()];
+                nodes.push(node(n.id.clone(), RuleId::AndR, sequent, premises));
+                nodes.extend([to, from]);
+            }
+            (Connective::Iff, RuleId::IffL) => {
+                let (a, b) = principal();
+                let second = node(
+                    fresh(&format!("{}_and2", n.id)),
+                    RuleId::AndL2,
+                    Sequent {
+                        ctx: extend(&sequent.ctx, [&imp(&a, &b)]),
+                        succ: sequent.succ.clone(),
+                    },
+                    n.premises.clone(),
+                );
+                let premises = vec![second.id.clone()];
+                nodes.push(node(n.id.clone(), RuleId::AndL1, sequent, premises));
+                nodes.push(second);
+            }
+            _ => nodes.push(ProofNode {
+                sequent,
+                ..n.clone()
+            }),
+        }
+    }
+
+    Ok(Proof { nodes, ..resolved })
+}
+
+fn imp(a: &Formula, b: &Formula) -> Formula {
+    Formula::Node(FormulaNode::Imp(Box::new(a.clone()), Box::new(b.clone())))
+}
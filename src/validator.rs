@@ -1,64 +1,1057 @@
-use anyhow::{bail, Result};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+use std::fmt;
+
+use serde::Serialize;
+use thiserror::Error;
 
 use crate::{
-    ast::{Proof, ProofNode},
-    registry::{RuleId, RuleId::*},
+    ast::{
+        Calculus, Contexts, DbFormula, Formula, FormulaNode, Proof, ProofNode, Sequent, Term,
+        TermNode,
+    },
+    parse::{resolve_sequent, resolve_term, ParseError},
+    registry::{Registry, RuleId, RuleId::*},
+    restrict::Restriction,
+    schema::RuleSchema,
 };
 
-/// Map rule strings (as they appear in JSON/examples) to RuleId.
-/// Names/case match tests exactly, plus a few common aliases.
-fn rule_from_str(s: &str) -> Option<RuleId> {
-    Some(match s {
-        // Core spellings
-        "Id" => Id,
-        "BotI" => BotI,
-        "AndR" => AndR,
-        "AndL1" => AndL1,
-        "AndL2" => AndL2,
-        "OrL" => OrL,
-        "Or1" => Or1,
-        "Or2" => Or2,
-        "ImpL" => ImpL,
-        "ImpR" => ImpR,
-        "Cut" => Cut,
-
-        // Tolerated aliases used in examples/golden data
-        "Axiom" | "Ax" => Id,
-        "OrR1" | "∨R1" => Or1,
-        "OrR2" | "∨R2" => Or2,
-        "∨L" => OrL,
-        "∧R" => AndR,
-        "∧L1" => AndL1,
-        "∧L2" => AndL2,
-        "→L" => ImpL,
-        "→R" => ImpR,
-
-        _ => return None,
-    })
-}
-
-/// Minimal local well‑formedness:
+/// Why a proof is not well-formed.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ValidationError {
+    #[error("root id not found: {root}")]
+    RootMissing { root: String },
+    #[error("unknown rule at node {node}: {rule}")]
+    UnknownRule { node: String, rule: String },
+    #[error("premise {premise} of node {node} not found")]
+    DanglingPremise { node: String, premise: String },
+    #[error("duplicate node id: {node}")]
+    DuplicateId { node: String },
+    /// Premises lead back to a node; `path` starts and ends at that node.
+    #[error("premise cycle: {}", .path.join(" → "))]
+    Cycle { path: Vec<String> },
+    /// A formula written as text does not parse; the error names the node.
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    /// The node's sequent does not follow from its premises by its rule.
+    #[error("node {node} fails rule {rule}: {reason}")]
+    RuleCheck {
+        node: String,
+        rule: RuleId,
+        reason: String,
+    },
+    /// The node's rule is not enabled at the time validated against.
+    #[error("node {node} uses {rule}, which is not enabled at t={t}")]
+    RuleNotEnabled { node: String, rule: String, t: u64 },
+    /// The node's rule is enabled at the time validated against, but the
+    /// inference breaks a restriction on it.
+    #[error("node {node} breaks the {restriction} restriction on {rule} at t={t}")]
+    Restricted {
+        node: String,
+        rule: String,
+        restriction: Restriction,
+        t: u64,
+    },
+    /// The node's sequent is not an instance of its rule schema.
+    #[error("node {node} fails rule {rule}: {reason}")]
+    SchemaCheck {
+        node: String,
+        rule: String,
+        reason: String,
+    },
+}
+
+/// Local well‑formedness:
 /// - root id exists
-/// - each rule name is known
+/// - node ids are unique
+/// - each rule name is known (built-in; see [`validate_with`] for schemas)
 /// - each premise id exists
-pub fn validate_local_wf(proof: &Proof) -> Result<()> {
+/// - premises do not form a cycle
+/// - each formula written as text parses
+/// - each node's sequent follows from its premises' sequents by its rule
+///   (see [`check_rule`])
+///
+/// Returns the first problem found; [`validate_all`] reports every one.
+pub fn validate_local_wf(proof: &Proof) -> Result<(), ValidationError> {
+    match errors(proof, None, None).into_iter().next() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// [`validate_local_wf`], also accepting the rule schemas `reg` declares:
+/// a node naming one is checked by [`RuleSchema::check`], and built-in
+/// rules may go by any name a slice gives them. Whether the rule is
+/// enabled at any particular time is not checked.
+pub fn validate_with(proof: &Proof, reg: &Registry) -> Result<(), ValidationError> {
+    match errors(proof, Some(reg), None).into_iter().next() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// [`validate_with`], also requiring every rule used to be enabled in
+/// `reg` at time `t`, within the restrictions on it there: is the proof
+/// valid under that kernel version?
+pub fn validate_at(proof: &Proof, reg: &Registry, t: u64) -> Result<(), ValidationError> {
+    match errors(proof, Some(reg), Some(t)).into_iter().next() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// How bad a [`Diagnostic`] is; only errors make a proof invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// One problem found by [`validate_all`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// The node the problem is at, if it is at one.
+    pub node: Option<String>,
+    /// A stable, machine-readable name for the kind of problem.
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl ValidationError {
+    /// The diagnostic code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ValidationError::RootMissing { .. } => "root-missing",
+            ValidationError::UnknownRule { .. } => "unknown-rule",
+            ValidationError::RuleNotEnabled { .. } => "rule-not-enabled",
+            ValidationError::Restricted { .. } => "rule-restricted",
+            ValidationError::DanglingPremise { .. } => "dangling-premise",
+            ValidationError::DuplicateId { .. } => "duplicate-id",
+            ValidationError::Cycle { .. } => "cycle",
+            ValidationError::Parse(_) => "parse",
+            ValidationError::RuleCheck { .. } | ValidationError::SchemaCheck { .. } => "rule-check",
+        }
+    }
+
+    /// The node the error is at, if any.
+    pub fn node(&self) -> Option<&str> {
+        match self {
+            ValidationError::RootMissing { .. } => None,
+            ValidationError::UnknownRule { node, .. }
+            | ValidationError::RuleNotEnabled { node, .. }
+            | ValidationError::Restricted { node, .. }
+            | ValidationError::DanglingPremise { node, .. }
+            | ValidationError::DuplicateId { node }
+            | ValidationError::RuleCheck { node, .. }
+            | ValidationError::SchemaCheck { node, .. } => Some(node),
+            ValidationError::Cycle { path } => path.first().map(String::as_str),
+            ValidationError::Parse(e) => e.node.as_deref(),
+        }
+    }
+}
+
+impl From<ValidationError> for Diagnostic {
+    fn from(e: ValidationError) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            node: e.node().map(str::to_string),
+            code: e.code(),
+            message: e.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{severity}[{}]: {}", self.code, self.message)
+    }
+}
+
+/// Every well-formedness problem in `proof`, in the order
+/// [`validate_local_wf`] would meet them. A node whose rule, premises or
+/// sequents are already broken is not checked against its rule.
+pub fn validate_all(proof: &Proof) -> Vec<Diagnostic> {
+    errors(proof, None, None)
+        .into_iter()
+        .map(Diagnostic::from)
+        .collect()
+}
+
+/// [`validate_all`], accepting the rule schemas `reg` declares as
+/// [`validate_with`] does.
+pub fn validate_all_with(proof: &Proof, reg: &Registry) -> Vec<Diagnostic> {
+    errors(proof, Some(reg), None)
+        .into_iter()
+        .map(Diagnostic::from)
+        .collect()
+}
+
+/// [`validate_all`], with the checks of [`validate_at`].
+pub fn validate_all_at(proof: &Proof, reg: &Registry, t: u64) -> Vec<Diagnostic> {
+    errors(proof, Some(reg), Some(t))
+        .into_iter()
+        .map(Diagnostic::from)
+        .collect()
+}
+
+/// With a registry, nodes may use its rule schemas and the names its
+/// slices give rules; `at` is the time whose enabled rules the proof may
+/// use, if that is checked at all.
+fn errors(proof: &Proof, reg: Option<&Registry>, at: Option<u64>) -> Vec<ValidationError> {
+    let schemas: &[RuleSchema] = reg.map_or(&[], |r| &r.rules);
+    let schema = |name: &str| schemas.iter().find(|s| s.name == name);
+    let kind = |name: &str| match reg {
+        Some(reg) => reg.rule_id(name, None),
+        None => RuleId::from_name(name),
+    };
+    let mut errs = Vec::new();
     if !proof.nodes.iter().any(|n| n.id == proof.root) {
-        bail!("root id not found: {}", proof.root);
+        errs.push(ValidationError::RootMissing {
+            root: proof.root.clone(),
+        });
     }
 
-    for ProofNode {
-        id, rule, premises, ..
-    } in &proof.nodes
+    // The first node with each id; later ones are reported and skipped.
+    let mut by_id: HashMap<&str, &ProofNode> = HashMap::new();
+    // Indices of nodes that cannot be checked against their rule.
+    let mut broken = HashSet::new();
+    for (i, node) in proof.nodes.iter().enumerate() {
+        match by_id.entry(&node.id) {
+            Entry::Vacant(e) => {
+                e.insert(node);
+            }
+            Entry::Occupied(_) => {
+                errs.push(ValidationError::DuplicateId {
+                    node: node.id.clone(),
+                });
+                broken.insert(i);
+            }
+        }
+    }
+
+    for (
+        i,
+        ProofNode {
+            id, rule, premises, ..
+        },
+    ) in proof.nodes.iter().enumerate()
     {
-        if rule_from_str(rule).is_none() {
-            bail!("unknown rule at node {}: {}", id, rule);
+        if kind(rule).is_none() && schema(rule).is_none() {
+            errs.push(ValidationError::UnknownRule {
+                node: id.clone(),
+                rule: rule.clone(),
+            });
+            broken.insert(i);
+        } else if let Some((_, t)) = reg.zip(at).filter(|(reg, t)| !reg.is_enabled(rule, *t)) {
+            errs.push(ValidationError::RuleNotEnabled {
+                node: id.clone(),
+                rule: rule.clone(),
+                t,
+            });
         }
         for prem in premises {
-            if !proof.nodes.iter().any(|n| n.id == *prem) {
-                bail!("premise {} of node {} not found", prem, id);
+            if !by_id.contains_key(prem.as_str()) {
+                errs.push(ValidationError::DanglingPremise {
+                    node: id.clone(),
+                    premise: prem.clone(),
+                });
+                broken.insert(i);
             }
         }
     }
 
+    errs.extend(cycles(proof, &by_id));
+
+    let mut resolved: HashMap<&str, Sequent> = HashMap::new();
+    for node in &proof.nodes {
+        match resolve_sequent(&node.sequent) {
+            Ok(s) => {
+                resolved.entry(&node.id).or_insert(s);
+            }
+            Err(e) => errs.push(e.at_node(&node.id).into()),
+        }
+    }
+
+    let end = resolved.get(proof.root.as_str());
+    for (i, node) in proof.nodes.iter().enumerate() {
+        if broken.contains(&i) {
+            continue;
+        }
+        let (Some(concl), Some(prems)) = (
+            resolved.get(node.id.as_str()),
+            node.premises
+                .iter()
+                .map(|p| resolved.get(p.as_str()))
+                .collect::<Option<Vec<&Sequent>>>(),
+        ) else {
+            continue;
+        };
+        let Some(kind) = kind(&node.rule) else {
+            let schema = schema(&node.rule).expect("checked above");
+            if let Err(reason) = schema.check(proof.calculus, proof.contexts, concl, &prems) {
+                errs.push(ValidationError::SchemaCheck {
+                    node: node.id.clone(),
+                    rule: schema.name.clone(),
+                    reason,
+                });
+            }
+            continue;
+        };
+        let term = match node.term.as_ref().map(resolve_term).transpose() {
+            Ok(term) => term,
+            Err(e) => {
+                errs.push(e.at_node(&node.id).into());
+                continue;
+            }
+        };
+        let checked = check_rule(
+            proof.calculus,
+            proof.contexts,
+            kind,
+            concl,
+            &prems,
+            term.as_ref(),
+        );
+        if let Err(reason) = checked {
+            errs.push(ValidationError::RuleCheck {
+                node: node.id.clone(),
+                rule: kind,
+                reason,
+            });
+        } else if let (Some((reg, t)), Some(end)) = (reg.zip(at), end) {
+            for &restriction in reg.restrictions_at(kind, t) {
+                if !restriction.holds(kind, concl, &prems, end) {
+                    errs.push(ValidationError::Restricted {
+                        node: node.id.clone(),
+                        rule: node.rule.clone(),
+                        restriction,
+                        t,
+                    });
+                }
+            }
+        }
+    }
+
+    errs
+}
+
+/// One [`ValidationError::Cycle`] per premise edge that leads back into the
+/// current path of a depth-first walk.
+fn cycles(proof: &Proof, by_id: &HashMap<&str, &ProofNode>) -> Vec<ValidationError> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mark {
+        Active,
+        Done,
+    }
+
+    fn walk<'a>(
+        id: &'a str,
+        by_id: &HashMap<&'a str, &'a ProofNode>,
+        marks: &mut HashMap<&'a str, Mark>,
+        path: &mut Vec<&'a str>,
+        out: &mut Vec<ValidationError>,
+    ) {
+        marks.insert(id, Mark::Active);
+        path.push(id);
+        for prem in by_id.get(id).map_or(&[][..], |n| &n.premises[..]) {
+            match marks.get(prem.as_str()) {
+                Some(Mark::Active) => {
+                    let start = path.iter().position(|p| p == prem).expect("on path");
+                    let mut cycle: Vec<String> =
+                        path[start..].iter().map(|s| s.to_string()).collect();
+                    cycle.push(prem.clone());
+                    out.push(ValidationError::Cycle { path: cycle });
+                }
+                Some(Mark::Done) => {}
+                None if by_id.contains_key(prem.as_str()) => walk(prem, by_id, marks, path, out),
+                None => {}
+            }
+        }
+        path.pop();
+        marks.insert(id, Mark::Done);
+    }
+
+    let mut marks = HashMap::new();
+    let mut out = Vec::new();
+    for node in &proof.nodes {
+        if !marks.contains_key(node.id.as_str()) {
+            walk(&node.id, by_id, &mut marks, &mut Vec::new(), &mut out);
+        }
+    }
+    out
+}
+
+/// Nodes that cannot be reached from the root through premises, as
+/// warnings. They do not make a proof invalid.
+pub fn unreachable_warnings(proof: &Proof) -> Vec<Diagnostic> {
+    let mut seen = HashSet::new();
+    let mut stack = vec![proof.root.as_str()];
+    while let Some(id) = stack.pop() {
+        if seen.insert(id) {
+            if let Some(n) = proof.node(id) {
+                stack.extend(n.premises.iter().map(String::as_str));
+            }
+        }
+    }
+    proof
+        .nodes
+        .iter()
+        .filter(|n| !seen.contains(n.id.as_str()))
+        .map(|n| Diagnostic {
+            severity: Severity::Warning,
+            node: Some(n.id.clone()),
+            code: "unreachable",
+            message: format!(
+                "node {} is not reachable from the root {}",
+                n.id, proof.root
+            ),
+        })
+        .collect()
+}
+
+/// Check one inference: does `concl` follow from `prems` by `rule`?
+/// On failure, returns the side condition that does not hold.
+///
+/// With set contexts, both sides absorb weakening, so a premise may use
+/// any subset of the formulas shown (`Γ` on the left, `Δ` on the right);
+/// rules may keep their principal formula in the premise. When `contexts`
+/// count formulas, a premise has exactly the formulas shown, and the
+/// principal formula is not among `Γ, Δ`; under lists it and the formulas
+/// a rule adds stand at the left end of `Γ` and the right end of `Δ`.
+/// Axioms allow any context either way.
+/// In the intuitionistic calculus every sequent has exactly one formula on
+/// the right, so `Δ` is empty below and `C` is the conclusion.
+///
+/// ```text
+/// Id     ─────────────                       A ∈ Γ
+///        Γ ⊢ Δ, A
+/// BotI   ─────────────                       ⊥ ∈ Γ
+///        Γ ⊢ Δ
+/// TopR   ─────────────
+///        Γ ⊢ Δ, ⊤
+/// AndL1  Γ, A ⊢ Δ ⟹ Γ ⊢ Δ                   A ∧ B ∈ Γ   (AndL2: Γ, B ⊢ Δ)
+/// AndR   Γ ⊢ Δ, A   Γ ⊢ Δ, B ⟹ Γ ⊢ Δ, A ∧ B
+/// OrL    Γ, A ⊢ Δ   Γ, B ⊢ Δ ⟹ Γ ⊢ Δ        A ∨ B ∈ Γ
+/// Or1    Γ ⊢ Δ, A ⟹ Γ ⊢ Δ, A ∨ B                        (Or2: Γ ⊢ Δ, B)
+/// OrR    Γ ⊢ Δ, A, B ⟹ Γ ⊢ Δ, A ∨ B                     (classical)
+/// ImpL   Γ ⊢ Δ, A   Γ, B ⊢ Δ ⟹ Γ ⊢ Δ        A ⇒ B ∈ Γ
+/// ImpR   Γ, A ⊢ Δ, B ⟹ Γ ⊢ Δ, A ⇒ B
+/// NotL   Γ ⊢ Δ, A ⟹ Γ ⊢ Δ                   ¬A ∈ Γ
+/// NotR   Γ, A ⊢ Δ, ⊥ ⟹ Γ ⊢ Δ, ¬A
+/// IffL   Γ, A ⇒ B, B ⇒ A ⊢ Δ ⟹ Γ ⊢ Δ         A ⇔ B ∈ Γ
+/// IffR   Γ, A ⊢ Δ, B   Γ, B ⊢ Δ, A ⟹ Γ ⊢ Δ, A ⇔ B
+/// ForallL Γ, A[t/x] ⊢ Δ ⟹ Γ ⊢ Δ              ∀x A ∈ Γ
+/// ForallR Γ ⊢ Δ, A[y/x] ⟹ Γ ⊢ Δ, ∀x A        y not free in Γ, Δ
+/// ExistsL Γ, A[y/x] ⊢ Δ ⟹ Γ ⊢ Δ              ∃x A ∈ Γ, y not free in Γ, Δ
+/// ExistsR Γ ⊢ Δ, A[t/x] ⟹ Γ ⊢ Δ, ∃x A
+/// Cut    Γ ⊢ Δ, A   Γ, A ⊢ Δ ⟹ Γ ⊢ Δ
+/// WeakL  Γ ⊢ Δ ⟹ A, Γ ⊢ Δ                               (WeakR: Γ ⊢ Δ, A)
+/// ContrL A, A, Γ ⊢ Δ ⟹ A, Γ ⊢ Δ                         (ContrR: Γ ⊢ Δ, A)
+/// ExchL  Γ, A, B, Π ⊢ Δ ⟹ Γ, B, A, Π ⊢ Δ                (ExchR: on the right)
+/// ```
+///
+/// The right-hand structural rules need the classical calculus. How they
+/// read contexts depends on `contexts`: as sets they only ask that the
+/// premise's formulas appear in the conclusion; as multisets they must add,
+/// drop or keep exactly the copies shown; as lists the positions shown
+/// matter too (the left end of `Γ`, the right end of `Δ`).
+///
+/// `term` is the node's instantiation: the witness `t` of `ForallL` and
+/// `ExistsR`, or the eigenvariable `y` of `ForallR` and `ExistsL`.
+///
+/// Formulas are matched up to alpha-equivalence throughout, so `Id` closes
+/// `∀x P(x) ⊢ ∀y P(y)` and a cut on `∀x P(x)` may continue with `∀y P(y)`.
+pub fn check_rule(
+    calculus: Calculus,
+    contexts: Contexts,
+    rule: RuleId,
+    concl: &Sequent,
+    prems: &[&Sequent],
+    term: Option<&Term>,
+) -> Result<(), String> {
+    let arity = arity(rule);
+    if prems.len() != arity {
+        return Err(format!(
+            "expected {} premise(s), found {}",
+            arity,
+            prems.len()
+        ));
+    }
+    if calculus == Calculus::Intuitionistic {
+        if concl.succ.len() != 1 {
+            return Err(format!(
+                "conclusion has {} formulas on the right; intuitionistic sequents have one",
+                concl.succ.len()
+            ));
+        }
+        if let Some(n) = prems.iter().position(|p| p.succ.len() != 1) {
+            return Err(format!(
+                "premise {} has {} formulas on the right; intuitionistic sequents have one",
+                n + 1,
+                prems[n].succ.len()
+            ));
+        }
+    }
+
+    let gamma = &concl.ctx;
+    let delta = &concl.succ;
+    match rule {
+        Id => require(
+            gamma.iter().any(|f| has(delta, f)),
+            "conclusion is not among the hypotheses",
+        ),
+        BotI => require(gamma.iter().any(is_bot), "⊥ is not among the hypotheses"),
+        TopR => require(delta.iter().any(is_top), "conclusion is not ⊤"),
+
+        Cut => {
+            let Some(first) = prems[0].succ.first() else {
+                return Err("premise 1 has no cut formula".into());
+            };
+            let kept = carried(calculus, delta);
+            let cut = |a: &Formula| {
+                premise(contexts, prems[0], 1, gamma, &[], kept, &[a])?;
+                premise(contexts, prems[1], 2, gamma, &[a], delta, &[])
+            };
+            if prems[0].succ.iter().any(|a| cut(a).is_ok()) {
+                return Ok(());
+            }
+            cut(first)
+        }
+
+        WeakL | ContrL | ExchL => structural(contexts, rule, concl, prems[0]),
+        WeakR | ContrR | ExchR => {
+            if calculus != Calculus::Classical {
+                return Err(format!("{rule} needs the classical calculus"));
+            }
+            structural(contexts, rule, concl, prems[0])
+        }
+
+        AndR | Or1 | Or2 | OrR | ImpR | NotR | IffR | ForallR | ExistsR => {
+            let (article, shape, fits) = shape(rule);
+            let missing = if delta.len() == 1 {
+                format!("conclusion is not {article} {shape}")
+            } else {
+                format!("no {shape} on the right")
+            };
+            principal(delta, fits, missing, |p| {
+                right_rule(contexts, rule, p, concl, prems, term)
+            })
+        }
+        AndL1 | AndL2 | OrL | ImpL | NotL | IffL | ForallL | ExistsL => {
+            let (_, shape, fits) = shape(rule);
+            let missing = format!("no {shape} among the hypotheses");
+            principal(gamma, fits, missing, |p| {
+                left_rule(calculus, contexts, rule, p, concl, prems, term)
+            })
+        }
+    }
+}
+
+/// The connective a logical rule acts on.
+fn shape(rule: RuleId) -> (&'static str, &'static str, fn(&FormulaNode) -> bool) {
+    match rule {
+        AndL1 | AndL2 | AndR => ("a", "conjunction", |n| matches!(n, FormulaNode::And(..))),
+        OrL | Or1 | Or2 | OrR => ("a", "disjunction", |n| matches!(n, FormulaNode::Or(..))),
+        NotL | NotR => ("a", "negation", |n| matches!(n, FormulaNode::Not(..))),
+        IffL | IffR => ("a", "biconditional", |n| matches!(n, FormulaNode::Iff(..))),
+        ForallL | ForallR => ("a", "universal formula", |n| {
+            matches!(n, FormulaNode::Forall(..))
+        }),
+        ExistsL | ExistsR => ("an", "existential formula", |n| {
+            matches!(n, FormulaNode::Exists(..))
+        }),
+        _ => ("an", "implication", |n| matches!(n, FormulaNode::Imp(..))),
+    }
+}
+
+/// Try `check` on every formula of `side` with the right connective; the
+/// rule holds if one of them works as its principal formula.
+fn principal(
+    side: &[Formula],
+    fits: fn(&FormulaNode) -> bool,
+    missing: String,
+    check: impl Fn(&Formula) -> Result<(), String>,
+) -> Result<(), String> {
+    let candidates: Vec<&Formula> = side
+        .iter()
+        .filter(|f| as_node(f).is_some_and(fits))
+        .collect();
+    let Some(first) = candidates.first() else {
+        return Err(missing);
+    };
+    if candidates.iter().any(|p| check(p).is_ok()) {
+        return Ok(());
+    }
+    // Report against the first candidate; the others failed as well.
+    check(first)
+}
+
+/// Check a right rule against a chosen principal formula `p ∈ Δ`.
+fn right_rule(
+    contexts: Contexts,
+    rule: RuleId,
+    p: &Formula,
+    concl: &Sequent,
+    prems: &[&Sequent],
+    term: Option<&Term>,
+) -> Result<(), String> {
+    let gamma = &concl.ctx;
+    let delta = &without(contexts, &concl.succ, p, false)?;
+    match (rule, as_node(p)) {
+        (AndR, Some(FormulaNode::And(a, b))) => {
+            premise(contexts, prems[0], 1, gamma, &[], delta, &[a])?;
+            premise(contexts, prems[1], 2, gamma, &[], delta, &[b])
+        }
+        (Or1, Some(FormulaNode::Or(a, _))) | (Or2, Some(FormulaNode::Or(_, a))) => {
+            premise(contexts, prems[0], 1, gamma, &[], delta, &[a])
+        }
+        (OrR, Some(FormulaNode::Or(a, b))) => {
+            premise(contexts, prems[0], 1, gamma, &[], delta, &[a, b])
+        }
+        (ImpR, Some(FormulaNode::Imp(a, b))) => {
+            premise(contexts, prems[0], 1, gamma, &[a], delta, &[b])
+        }
+        (NotR, Some(FormulaNode::Not(a))) => premise(
+            contexts,
+            prems[0],
+            1,
+            gamma,
+            &[a],
+            delta,
+            &[&Formula::Node(FormulaNode::Bot)],
+        ),
+        (IffR, Some(FormulaNode::Iff(a, b))) => {
+            premise(contexts, prems[0], 1, gamma, &[a], delta, &[b])?;
+            premise(contexts, prems[1], 2, gamma, &[b], delta, &[a])
+        }
+        (ForallR, Some(FormulaNode::Forall(x, a))) => {
+            let y = eigenvariable(term, concl)?;
+            premise(contexts, prems[0], 1, gamma, &[], delta, &[&a.subst(x, y)])
+        }
+        (ExistsR, Some(FormulaNode::Exists(x, a))) => premise(
+            contexts,
+            prems[0],
+            1,
+            gamma,
+            &[],
+            delta,
+            &[&a.subst(x, witness(term)?)],
+        ),
+        _ => Err("principal formula has the wrong connective".into()),
+    }
+}
+
+/// Check a left rule against a chosen principal formula `p ∈ Γ`.
+fn left_rule(
+    calculus: Calculus,
+    contexts: Contexts,
+    rule: RuleId,
+    p: &Formula,
+    concl: &Sequent,
+    prems: &[&Sequent],
+    term: Option<&Term>,
+) -> Result<(), String> {
+    let gamma = &without(contexts, &concl.ctx, p, true)?;
+    let delta = &concl.succ;
+    let kept = carried(calculus, delta);
+    match (rule, as_node(p)) {
+        (AndL1, Some(FormulaNode::And(a, _))) | (AndL2, Some(FormulaNode::And(_, a))) => {
+            premise(contexts, prems[0], 1, gamma, &[a], delta, &[])
+        }
+        (OrL, Some(FormulaNode::Or(a, b))) => {
+            premise(contexts, prems[0], 1, gamma, &[a], delta, &[])?;
+            premise(contexts, prems[1], 2, gamma, &[b], delta, &[])
+        }
+        (ImpL, Some(FormulaNode::Imp(a, b))) => {
+            premise(contexts, prems[0], 1, gamma, &[], kept, &[a])?;
+            premise(contexts, prems[1], 2, gamma, &[b], delta, &[])
+        }
+        (NotL, Some(FormulaNode::Not(a))) => premise(contexts, prems[0], 1, gamma, &[], kept, &[a]),
+        (IffL, Some(FormulaNode::Iff(a, b))) => {
+            let imp = |x: &Formula, y: &Formula| {
+                Formula::Node(FormulaNode::Imp(Box::new(x.clone()), Box::new(y.clone())))
+            };
+            premise(
+                contexts,
+                prems[0],
+                1,
+                gamma,
+                &[&imp(a, b), &imp(b, a)],
+                delta,
+                &[],
+            )
+        }
+        (ForallL, Some(FormulaNode::Forall(x, a))) => premise(
+            contexts,
+            prems[0],
+            1,
+            gamma,
+            &[&a.subst(x, witness(term)?)],
+            delta,
+            &[],
+        ),
+        (ExistsL, Some(FormulaNode::Exists(x, a))) => {
+            let y = eigenvariable(term, concl)?;
+            premise(contexts, prems[0], 1, gamma, &[&a.subst(x, y)], delta, &[])
+        }
+        _ => Err("principal formula has the wrong connective".into()),
+    }
+}
+
+/// The witness term of `ForallL` or `ExistsR`.
+fn witness(term: Option<&Term>) -> Result<&Term, String> {
+    term.ok_or_else(|| "no instantiation term given".to_string())
+}
+
+/// The eigenvariable of `ForallR` or `ExistsL`, which must not occur free
+/// in the conclusion.
+fn eigenvariable<'a>(term: Option<&'a Term>, concl: &Sequent) -> Result<&'a Term, String> {
+    match term {
+        Some(Term::Node(TermNode::Var(y))) if concl.free_vars().contains(y) => {
+            Err(format!("eigenvariable {y} occurs free in the conclusion"))
+        }
+        Some(t @ Term::Node(TermNode::Var(_))) => Ok(t),
+        Some(t) => Err(format!("eigenvariable must be a variable, found {t}")),
+        None => Err("no eigenvariable given".into()),
+    }
+}
+
+/// The principal formula of a logical inference: the first formula of the
+/// right shape for which `rule` checks. Used to rewrite inferences.
+pub(crate) fn principal_formula<'a>(
+    rule: RuleId,
+    concl: &'a Sequent,
+    prems: &[&Sequent],
+    term: Option<&Term>,
+) -> Option<&'a Formula> {
+    let (_, _, fits) = shape(rule);
+    let left = matches!(
+        rule,
+        AndL1 | AndL2 | OrL | ImpL | NotL | IffL | ForallL | ExistsL
+    );
+    let side = if left { &concl.ctx } else { &concl.succ };
+    side.iter()
+        .filter(|f| as_node(f).is_some_and(fits))
+        .find(|p| {
+            let fits = if left {
+                left_rule(
+                    Calculus::Classical,
+                    Contexts::Set,
+                    rule,
+                    p,
+                    concl,
+                    prems,
+                    term,
+                )
+            } else {
+                right_rule(Contexts::Set, rule, p, concl, prems, term)
+            };
+            prems.len() == arity(rule) && fits.is_ok()
+        })
+}
+
+/// Is `p` a principal formula for which the left rule `rule` fits?
+/// Used by cut elimination to recognise principal cuts.
+pub(crate) fn is_left_principal(
+    rule: RuleId,
+    p: &Formula,
+    concl: &Sequent,
+    prems: &[&Sequent],
+    term: Option<&Term>,
+) -> bool {
+    has(&concl.ctx, p)
+        && prems.len() == arity(rule)
+        && left_rule(
+            Calculus::Classical,
+            Contexts::Set,
+            rule,
+            p,
+            concl,
+            prems,
+            term,
+        )
+        .is_ok()
+}
+
+/// Number of premises each rule takes.
+fn arity(rule: RuleId) -> usize {
+    match rule {
+        Id | BotI | TopR => 0,
+        AndL1 | AndL2 | Or1 | Or2 | OrR | ImpR | NotL | NotR | IffL | ForallL | ForallR
+        | ExistsL | ExistsR | WeakL | WeakR | ContrL | ContrR | ExchL | ExchR => 1,
+        AndR | OrL | ImpL | IffR | Cut => 2,
+    }
+}
+
+/// Premise `n` (1-based) must prove all of `goals`, from `Γ` plus `extra`,
+/// with nothing on the right beyond `Δ` and `goals`. When `contexts` count
+/// formulas, the premise has exactly `extra, Γ ⊢ Δ, goals`, copies and, for
+/// lists, order included.
+fn premise(
+    contexts: Contexts,
+    prem: &Sequent,
+    n: usize,
+    gamma: &[Formula],
+    extra: &[&Formula],
+    delta: &[Formula],
+    goals: &[&Formula],
+) -> Result<(), String> {
+    let proves = |f: &Formula| has(&prem.succ, f);
+    if let Some(goal) = goals.iter().find(|g| !proves(g)) {
+        return Err(match prem.thm() {
+            Some(thm) => format!("premise {n} proves {thm}, expected {goal}"),
+            None => format!("premise {n} does not prove {goal}"),
+        });
+    }
+    if contexts != Contexts::Set {
+        let ctx: Vec<Formula> = extra.iter().copied().chain(gamma).cloned().collect();
+        let succ: Vec<Formula> = delta.iter().chain(goals.iter().copied()).cloned().collect();
+        let order = if contexts == Contexts::List {
+            ", in this order"
+        } else {
+            ""
+        };
+        require(
+            same(contexts, &prem.ctx, &ctx),
+            &format!(
+                "premise {n} must have exactly the hypotheses {}{order}",
+                shown(&ctx)
+            ),
+        )?;
+        return require(
+            same(contexts, &prem.succ, &succ),
+            &format!("premise {n} must prove exactly {}{order}", shown(&succ)),
+        );
+    }
+    let allowed = extend(delta, goals.iter().copied());
+    if let Some(f) = prem.succ.iter().find(|f| !has(&allowed, f)) {
+        return Err(match allowed.as_slice() {
+            [only] => format!("premise {n} proves {f}, expected {only}"),
+            _ => format!("premise {n} proves {f}, which the conclusion does not"),
+        });
+    }
+    if !subset(&prem.ctx, &extend(gamma, extra.iter().copied())) {
+        return Err(format!(
+            "premise {n} uses hypotheses that are not available in the conclusion"
+        ));
+    }
     Ok(())
 }
+
+/// `side` less the principal formula `p`, when `contexts` count formulas.
+/// Under lists `p` must stand at the outer end: the left end of the
+/// hypotheses (`left`), the right end of the conclusions.
+fn without(
+    contexts: Contexts,
+    side: &[Formula],
+    p: &Formula,
+    left: bool,
+) -> Result<Vec<Formula>, String> {
+    let at = match contexts {
+        Contexts::Set => return Ok(side.to_vec()),
+        Contexts::Multiset => side.iter().position(|f| f.alpha_eq(p)),
+        Contexts::List if left => Some(0),
+        Contexts::List => side.len().checked_sub(1),
+    };
+    let end = if left { "left" } else { "right" };
+    match at {
+        Some(i) if side[i].alpha_eq(p) => {
+            let mut rest = side.to_vec();
+            rest.remove(i);
+            Ok(rest)
+        }
+        _ => Err(format!("principal formula {p} is not at the {end} end")),
+    }
+}
+
+/// The conclusions a premise that proves a new formula shares with the
+/// conclusion: none in the intuitionistic calculus, where the new formula
+/// takes the place of `C`.
+fn carried(calculus: Calculus, delta: &[Formula]) -> &[Formula] {
+    match calculus {
+        Calculus::Intuitionistic => &[],
+        Calculus::Classical => delta,
+    }
+}
+
+/// Do `a` and `b` hold the same formulas under `contexts`?
+fn same(contexts: Contexts, a: &[Formula], b: &[Formula]) -> bool {
+    match contexts {
+        Contexts::List => forms(a) == forms(b),
+        _ => sorted(forms(a)) == sorted(forms(b)),
+    }
+}
+
+fn shown(side: &[Formula]) -> String {
+    if side.is_empty() {
+        return "nothing".into();
+    }
+    let side: Vec<String> = side.iter().map(Formula::to_string).collect();
+    side.join(", ")
+}
+
+/// Check a structural rule under the given context discipline.
+fn structural(
+    contexts: Contexts,
+    rule: RuleId,
+    concl: &Sequent,
+    prem: &Sequent,
+) -> Result<(), String> {
+    if contexts == Contexts::Set {
+        return premise(contexts, prem, 1, &concl.ctx, &[], &concl.succ, &[]);
+    }
+    let left = matches!(rule, WeakL | ContrL | ExchL);
+    // `p`/`c` is the side the rule acts on, `op`/`oc` the other one.
+    let (name, other, p, c, op, oc) = if left {
+        (
+            "left",
+            "right",
+            &prem.ctx,
+            &concl.ctx,
+            &prem.succ,
+            &concl.succ,
+        )
+    } else {
+        (
+            "right",
+            "left",
+            &prem.succ,
+            &concl.succ,
+            &prem.ctx,
+            &concl.ctx,
+        )
+    };
+    let (p, c, op, oc) = (forms(p), forms(c), forms(op), forms(oc));
+    let list = contexts == Contexts::List;
+
+    let same_other = if list {
+        op == oc
+    } else {
+        sorted(op) == sorted(oc)
+    };
+    require(
+        same_other,
+        &format!("premise and conclusion differ on the {other}"),
+    )?;
+
+    // Under lists, the rule acts at the outer end: the front of the left
+    // side and the back of the right side.
+    let (p, c) = if list && !left {
+        (p.into_iter().rev().collect(), c.into_iter().rev().collect())
+    } else {
+        (p, c)
+    };
+    match (rule, list) {
+        (WeakL | WeakR, true) => require(
+            c.len() == p.len() + 1 && c[1..] == p[..],
+            &format!("conclusion must add one formula at the {name} end"),
+        ),
+        (WeakL | WeakR, false) => require(
+            c.len() == p.len() + 1 && minus(&c, &p).is_some(),
+            &format!("conclusion must add exactly one formula on the {name}"),
+        ),
+        (ContrL | ContrR, true) => require(
+            p.len() == c.len() + 1 && p.len() >= 2 && p[0] == p[1] && p[1..] == c[..],
+            &format!("premise must repeat the formula at the {name} end"),
+        ),
+        (ContrL | ContrR, false) => require(
+            minus(&p, &c).is_some_and(|a| c.contains(&a)),
+            &format!("premise must repeat exactly one formula on the {name}"),
+        ),
+        (_, true) => {
+            let i = p.iter().zip(&c).position(|(x, y)| x != y);
+            let swapped = p.len() == c.len()
+                && i.is_some_and(|i| {
+                    let mut q = p.clone();
+                    i + 1 < q.len() && {
+                        q.swap(i, i + 1);
+                        q == c
+                    }
+                });
+            require(
+                swapped,
+                &format!("conclusion must swap two neighbouring formulas on the {name}"),
+            )
+        }
+        (_, false) => require(
+            sorted(p) == sorted(c),
+            &format!("premise and conclusion differ on the {name}"),
+        ),
+    }
+}
+
+/// Formulas up to alpha-equivalence, in order.
+fn forms(side: &[Formula]) -> Vec<DbFormula> {
+    side.iter().map(Formula::de_bruijn).collect()
+}
+
+fn sorted(mut v: Vec<DbFormula>) -> Vec<DbFormula> {
+    v.sort();
+    v
+}
+
+/// The one formula `big` has beyond `small`, counting copies.
+fn minus(big: &[DbFormula], small: &[DbFormula]) -> Option<DbFormula> {
+    let mut rest = big.to_vec();
+    for f in small {
+        let i = rest.iter().position(|g| g == f)?;
+        rest.swap_remove(i);
+    }
+    match rest.as_slice() {
+        [f] => Some(f.clone()),
+        _ => None,
+    }
+}
+
+fn require(ok: bool, why: &str) -> Result<(), String> {
+    if ok {
+        Ok(())
+    } else {
+        Err(why.to_string())
+    }
+}
+
+// ---------- contexts (read as sets, up to alpha-equivalence) ----------
+
+/// Does `side` contain `f`, up to the names of bound variables?
+pub(crate) fn has(side: &[Formula], f: &Formula) -> bool {
+    side.iter().any(|g| g.alpha_eq(f))
+}
+
+pub(crate) fn subset(a: &[Formula], b: &[Formula]) -> bool {
+    a.iter().all(|f| has(b, f))
+}
+
+/// `ctx` followed by the formulas of `extra` it does not already contain.
+pub(crate) fn extend<'a>(
+    ctx: &[Formula],
+    extra: impl IntoIterator<Item = &'a Formula>,
+) -> Vec<Formula> {
+    let mut out = ctx.to_vec();
+    for f in extra {
+        if !has(&out, f) {
+            out.push(f.clone());
+        }
+    }
+    out
+}
+
+pub(crate) fn as_node(f: &Formula) -> Option<&FormulaNode> {
+    match f {
+        Formula::Node(n) => Some(n),
+        Formula::Text(_) => None,
+    }
+}
+
+pub(crate) fn is_bot(f: &Formula) -> bool {
+    matches!(as_node(f), Some(FormulaNode::Bot))
+}
+
+fn is_top(f: &Formula) -> bool {
+    matches!(as_node(f), Some(FormulaNode::Top))
+}
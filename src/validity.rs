@@ -0,0 +1,93 @@
+// src/validity.rs
+//! At which registry times is a proof valid?
+//!
+//! A registry only changes at the times of its slices and derivations, so
+//! those times cut the time line into ranges over which a proof's status
+//! is constant. For each range we say whether the proof is valid as
+//! written, becomes valid after [`transport`], or cannot be transported.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::{
+    ast::Proof,
+    registry::Registry,
+    transport::{error_chain, transport},
+    validator::{validate_at, validate_with, ValidationError},
+};
+
+/// The status of a proof over an [`Interval`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Validity {
+    /// Valid as written.
+    Valid,
+    /// Valid after transport to the time.
+    Transportable,
+    /// Transport fails, for `reason`.
+    Impossible { reason: String },
+}
+
+/// A range of registry times, `from` to `to` inclusive, or onwards when
+/// `to` is `None`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Interval {
+    pub from: u64,
+    pub to: Option<u64>,
+    #[serde(flatten)]
+    pub validity: Validity,
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.to {
+            Some(to) if to == self.from => write!(f, "t={}", self.from)?,
+            Some(to) => write!(f, "t={}..={to}", self.from)?,
+            None => write!(f, "t>={}", self.from)?,
+        }
+        match &self.validity {
+            Validity::Valid => write!(f, ": valid"),
+            Validity::Transportable => write!(f, ": valid after transport"),
+            Validity::Impossible { reason } => write!(f, ": cannot be transported: {reason}"),
+        }
+    }
+}
+
+/// The time ranges where `proof` is valid, transportable or neither,
+/// covering every time from 0 on, in order. Neighbouring ranges with the
+/// same status are merged.
+///
+/// The proof must pass [`validate_with`] against `reg`.
+pub fn validity_intervals(proof: &Proof, reg: &Registry) -> Result<Vec<Interval>, ValidationError> {
+    validate_with(proof, reg)?;
+
+    let mut starts = reg.change_times();
+    if starts.first() != Some(&0) {
+        starts.insert(0, 0);
+    }
+
+    let mut out: Vec<Interval> = Vec::new();
+    for (i, &t) in starts.iter().enumerate() {
+        let validity = if validate_at(proof, reg, t).is_ok() {
+            Validity::Valid
+        } else {
+            match transport(proof, reg, t, t) {
+                Ok(_) => Validity::Transportable,
+                Err(e) => Validity::Impossible {
+                    reason: error_chain(&e),
+                },
+            }
+        };
+        let to = starts.get(i + 1).map(|next| next - 1);
+        match out.last_mut() {
+            Some(last) if last.validity == validity => last.to = to,
+            _ => out.push(Interval {
+                from: t,
+                to,
+                validity,
+            }),
+        }
+    }
+    Ok(out)
+}
@@ -0,0 +1,91 @@
+// tests/alpha.rs
+use proof_transport::{ast::Sequent, parse_formula, parse_term};
+
+fn alpha(a: &str, b: &str) -> bool {
+    parse_formula(a)
+        .unwrap()
+        .alpha_eq(&parse_formula(b).unwrap())
+}
+
+#[test]
+fn bound_variables_may_be_renamed() {
+    assert!(alpha("∀x P(x)", "∀y P(y)"));
+    assert!(alpha("∀x ∃y R(x, y)", "∀a ∃b R(a, b)"));
+    assert!(alpha("∀x P(x) ∧ Q(z)", "∀w P(w) ∧ Q(z)"));
+
+    // Free variables must match, and binders must line up.
+    assert!(!alpha("∀x P(y)", "∀x P(z)"));
+    assert!(!alpha("∀x P(x)", "∀y P(x)"));
+    assert!(!alpha("∀x ∀y R(x, y)", "∀y ∀x R(x, y)"));
+    assert!(!alpha("∀x P(x)", "∃x P(x)"));
+}
+
+#[test]
+fn sequents_compare_contexts_as_sets() {
+    let seq = |ctx: &[&str], thm: &str| {
+        Sequent::new(
+            ctx.iter().map(|f| parse_formula(f).unwrap()).collect(),
+            parse_formula(thm).unwrap(),
+        )
+    };
+    assert!(seq(&["A", "∀x P(x)"], "B").alpha_eq(&seq(&["∀y P(y)", "A", "A"], "B")));
+    assert!(!seq(&["A"], "B").alpha_eq(&seq(&["A", "C"], "B")));
+    assert!(!seq(&["A"], "B").alpha_eq(&seq(&["A"], "C")));
+}
+
+#[test]
+fn substitution_avoids_capture() {
+    let subst =
+        |f: &str, x: &str, t: &str| parse_formula(f).unwrap().subst(x, &parse_term(t).unwrap());
+    assert_eq!(
+        subst("P(x) ∧ ∀x P(x)", "x", "f(a)"),
+        parse_formula("P(f(a)) ∧ ∀x P(x)").unwrap()
+    );
+    // `y` is free in the term, so the binder is renamed.
+    assert_eq!(
+        subst("∀y R(x, y)", "x", "y"),
+        parse_formula("∀y' R(y, y')").unwrap()
+    );
+    assert_eq!(
+        subst("∀y R(x, y, y')", "x", "g(y)"),
+        parse_formula("∀y'' R(g(y), y'', y')").unwrap()
+    );
+    // No free `x` below the binder: nothing to rename.
+    assert_eq!(
+        subst("∀y R(y, y)", "x", "y"),
+        parse_formula("∀y R(y, y)").unwrap()
+    );
+
+    let f = parse_formula("∀x R(x, z) ∨ ∃y P(y)").unwrap();
+    assert_eq!(f.free_vars().into_iter().collect::<Vec<_>>(), ["z"]);
+}
+
+#[test]
+fn de_bruijn_forms_identify_alpha_variants() {
+    use std::collections::HashSet;
+
+    let pairs = [
+        ("∀x ∃y R(x, y)", "∀a ∃b R(a, b)"),
+        ("∀x P(x) ∧ Q(z)", "∀w P(w) ∧ Q(z)"),
+        ("∀x ∀y R(x, y)", "∀y ∀x R(x, y)"),
+        ("∀x P(y)", "∀x P(z)"),
+    ];
+    for (a, b) in pairs {
+        let (fa, fb) = (parse_formula(a).unwrap(), parse_formula(b).unwrap());
+        assert_eq!(
+            fa.de_bruijn() == fb.de_bruijn(),
+            fa.alpha_eq(&fb),
+            "{a} vs {b}"
+        );
+    }
+
+    let classes: HashSet<_> = ["∀x P(x)", "∀y P(y)", "∃x P(x)"]
+        .iter()
+        .map(|f| parse_formula(f).unwrap().de_bruijn())
+        .collect();
+    assert_eq!(classes.len(), 2);
+
+    let f = parse_formula("∀x (P(x) ⇒ ∃y R(x, y, z))").unwrap();
+    assert_eq!(f.bound_vars().into_iter().collect::<Vec<_>>(), ["x", "y"]);
+    assert_eq!(f.free_vars().into_iter().collect::<Vec<_>>(), ["z"]);
+}
@@ -0,0 +1,55 @@
+// tests/cert.rs
+use proof_transport::{
+    cert::{CertError, RewriteStep, Side},
+    registry::{Registry, RuleId},
+    transport::transport_with_certificate,
+};
+
+mod support;
+use support::load;
+
+fn registry() -> Registry {
+    Registry::from_path("examples/R.json").expect("registry")
+}
+
+#[test]
+fn certificate_records_transport_and_verifies() {
+    let p = load("examples/proof_cut_pair_valid.json");
+    let reg = registry();
+    let (q, cert) = transport_with_certificate(&p, &reg, 0, 1).expect("transport");
+
+    assert!(cert.rules_before.contains(&RuleId::Cut));
+    assert!(!cert.rules_after.contains(&RuleId::Cut));
+    assert!(cert.fragility_after < cert.fragility_before);
+    assert_eq!(cert.steps, vec![RewriteStep::CutElimination { removed: 3 }]);
+
+    // Round-trips through JSON and still checks out.
+    let json = serde_json::to_string(&cert).unwrap();
+    let back: proof_transport::Certificate = serde_json::from_str(&json).unwrap();
+    back.verify(&p, &q, Some(&reg))
+        .expect("certificate verifies");
+}
+
+#[test]
+fn certificate_rejects_other_proofs() {
+    let p = load("examples/proof_with_cut_valid.json");
+    let reg = registry();
+    let (q, cert) = transport_with_certificate(&p, &reg, 0, 1).expect("transport");
+
+    // Swapping input and output breaks the hashes.
+    assert!(matches!(
+        cert.verify(&q, &p, None),
+        Err(CertError::HashMismatch {
+            side: Side::Input,
+            ..
+        })
+    ));
+
+    // A registry that disagrees with the recorded slices is rejected.
+    let other: Registry =
+        Registry::from_json(r#"{"times": [{"t": 0, "enabled_rules": ["Id", "Cut"]}]}"#).unwrap();
+    assert_eq!(
+        cert.verify(&p, &q, Some(&other)),
+        Err(CertError::SliceMismatch { t: 0 })
+    );
+}
@@ -0,0 +1,172 @@
+// tests/cli.rs
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use proof_transport::{ast::Proof, validator::validate_local_wf};
+
+fn bin() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_proof-transport"))
+}
+
+fn tmp(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("proof-transport-{}-{name}", std::process::id()))
+}
+
+fn transport(proof: &str, registry: &str, out: &PathBuf) -> Output {
+    bin()
+        .args(["transport", proof, "--registry", registry, "--from", "0"])
+        .args(["--to", "1", "-o"])
+        .arg(out)
+        .output()
+        .expect("run proof-transport")
+}
+
+#[test]
+fn transport_writes_cut_free_proof_and_reports_delta() {
+    let out = tmp("transported.json");
+    let run = transport(
+        "examples/proof_with_cut_valid.json",
+        "examples/R.json",
+        &out,
+    );
+    assert_eq!(run.status.code(), Some(0), "{run:?}");
+
+    let stdout = String::from_utf8_lossy(&run.stdout);
+    assert!(stdout.contains("fragility delta -"), "stdout: {stdout}");
+
+    let q: Proof = serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+    std::fs::remove_file(&out).ok();
+    validate_local_wf(&q).expect("output is well-formed");
+    assert!(q.nodes.iter().all(|n| n.rule != "Cut"));
+}
+
+#[test]
+fn transport_reports_already_valid_proofs() {
+    let out = tmp("already.json");
+    let run = transport("examples/proof_cut_free.json", "examples/R.json", &out);
+    std::fs::remove_file(&out).ok();
+    assert_eq!(run.status.code(), Some(3), "{run:?}");
+}
+
+#[test]
+fn transport_reports_impossible_targets() {
+    // `Id` is gone at t=1 and nothing can replace it.
+    let reg = tmp("no-id.json");
+    std::fs::write(
+        &reg,
+        r#"{"times": [
+            {"t": 0, "enabled_rules": ["Id", "Cut"]},
+            {"t": 1, "enabled_rules": ["ImpR"]}
+        ]}"#,
+    )
+    .unwrap();
+    let out = tmp("impossible.json");
+    let run = transport(
+        "examples/proof_with_cut_valid.json",
+        reg.to_str().unwrap(),
+        &out,
+    );
+    std::fs::remove_file(&reg).ok();
+    assert_eq!(run.status.code(), Some(4), "{run:?}");
+    assert!(!out.exists());
+}
+
+#[test]
+fn validate_prints_json_diagnostics() {
+    let run = bin()
+        .args([
+            "validate",
+            "examples/proof_with_cut_valid.json",
+            "--format",
+            "json",
+        ])
+        .output()
+        .expect("run proof-transport");
+    assert_eq!(run.status.code(), Some(0), "{run:?}");
+    let diags: serde_json::Value = serde_json::from_slice(&run.stdout).unwrap();
+    assert_eq!(diags, serde_json::json!([]));
+}
+
+#[test]
+fn validate_accepts_registry_rule_schemas() {
+    let run = |extra: &[&str]| {
+        bin()
+            .args(["validate", "examples/kernel/and_swap.json"])
+            .args(extra)
+            .output()
+            .expect("run proof-transport")
+    };
+    assert_eq!(run(&[]).status.code(), Some(1));
+    let ok = run(&["--registry", "examples/kernel/R.json"]);
+    assert_eq!(ok.status.code(), Some(0), "{ok:?}");
+    assert_eq!(String::from_utf8_lossy(&ok.stdout).trim(), "ok");
+}
+
+#[test]
+fn validate_checks_rules_enabled_at_a_time() {
+    let run = |t: &str| {
+        bin()
+            .args(["validate", "examples/proof_with_cut_valid.json"])
+            .args(["--registry", "examples/R.json", "--at", t])
+            .output()
+            .expect("run proof-transport")
+    };
+    assert_eq!(run("0").status.code(), Some(0));
+    let at1 = run("1");
+    assert_eq!(at1.status.code(), Some(1), "{at1:?}");
+    let stdout = String::from_utf8_lossy(&at1.stdout);
+    assert!(
+        stdout.contains("error[rule-not-enabled]: node n0 uses Cut, which is not enabled at t=1"),
+        "{stdout}"
+    );
+
+    // `--at` needs a registry.
+    let bare = bin()
+        .args([
+            "validate",
+            "examples/proof_with_cut_valid.json",
+            "--at",
+            "1",
+        ])
+        .output()
+        .expect("run proof-transport");
+    assert_eq!(bare.status.code(), Some(2));
+}
+
+#[test]
+fn validity_lists_intervals() {
+    let run = bin()
+        .args(["validity", "examples/proof_with_cut_valid.json"])
+        .args(["--registry", "examples/R.json", "--format", "json"])
+        .output()
+        .expect("run proof-transport");
+    assert_eq!(run.status.code(), Some(0), "{run:?}");
+    let intervals: serde_json::Value = serde_json::from_slice(&run.stdout).unwrap();
+    assert_eq!(
+        intervals,
+        serde_json::json!([
+            { "from": 0, "to": 0, "status": "valid" },
+            { "from": 1, "to": null, "status": "transportable" }
+        ])
+    );
+}
+
+#[test]
+fn transport_help_lists_every_exit_code() {
+    let run = bin()
+        .args(["transport", "--help"])
+        .output()
+        .expect("run proof-transport");
+    assert_eq!(run.status.code(), Some(0), "{run:?}");
+    let help = String::from_utf8_lossy(&run.stdout);
+    let help = help.split_whitespace().collect::<Vec<_>>().join(" ");
+    for code in [
+        "0 if the proof was rewritten",
+        "3 if it was already valid at `--to`",
+        "4 if transport is impossible",
+        "5 if transport produced an invalid proof (an internal bug)",
+        "1 on any other error",
+    ] {
+        assert!(help.contains(code), "{code}: {help}");
+    }
+}
@@ -1,16 +1,17 @@
 use proof_transport::{
-    ast::Proof, cutelim::cut_eliminate_all, frag::fragility_score, validator::validate_local_wf,
+    ast::Proof, cutelim::cut_eliminate_all, frag::fragility_score, parse::resolve_proof,
+    validator::validate_local_wf,
 };
 use serde_json::from_reader;
 use std::fs::File;
 
 #[test]
 fn cut_elimination_rewrites_root_and_drops_fragility() {
-    let p: Proof = from_reader(File::open("examples/proof_with_cut.json").unwrap()).unwrap();
+    let p: Proof = from_reader(File::open("examples/proof_with_cut_valid.json").unwrap()).unwrap();
     validate_local_wf(&p).unwrap();
 
     let before_score = fragility_score(&p);
-    let q = cut_eliminate_all(&p);
+    let q = cut_eliminate_all(&p).unwrap();
     validate_local_wf(&q).unwrap();
 
     // Fragility must drop strictly (we removed the `Cut` root)
@@ -22,3 +23,67 @@ fn cut_elimination_rewrites_root_and_drops_fragility() {
         after_score
     );
 }
+
+/// Elimination must produce a cut-free proof of the *same* end-sequent,
+/// including when the cut formula is compound (`or_proof_with_cut_valid.json`
+/// goes through the principal `∨` case, the `¬` and `⇔` examples through
+/// theirs).
+#[test]
+fn cut_elimination_is_cut_free_and_keeps_end_sequent() {
+    for path in [
+        "examples/proof_with_cut_valid.json",
+        "examples/proof_cut_chain_valid.json",
+        "examples/proof_cut_pair_valid.json",
+        "examples/or_proof_with_cut_valid.json",
+        "examples/not_proof_with_cut.json",
+        "examples/iff_proof_with_cut.json",
+        "examples/proof_fo_quantifiers_valid.json",
+    ] {
+        let p: Proof = from_reader(File::open(path).unwrap()).unwrap();
+        let q = cut_eliminate_all(&p).unwrap();
+        validate_local_wf(&q).unwrap();
+
+        assert!(
+            q.nodes.iter().all(|n| n.rule != "Cut"),
+            "cut left behind in {path}"
+        );
+        // Text formulas come back parsed, so compare against the parsed input.
+        let p = resolve_proof(&p).unwrap();
+        assert_eq!(
+            p.root_node().unwrap().sequent,
+            q.root_node().unwrap().sequent,
+            "end-sequent changed in {path}"
+        );
+    }
+}
+
+#[test]
+fn prop_cut_in_reduces_to_prop_cut_out() {
+    let p: Proof = from_reader(File::open("examples/prop_cut_in_valid.json").unwrap()).unwrap();
+    let expected: Proof =
+        from_reader(File::open("examples/prop_cut_out_valid.json").unwrap()).unwrap();
+    assert_eq!(cut_eliminate_all(&p).unwrap(), expected);
+}
+
+/// Dropping an unused cut moves its right premise into the cut's context.
+/// Here that context mentions the eigenvariable of the `ForallR` there, so
+/// the eigenvariable has to be renamed apart.
+#[test]
+fn weakening_renames_eigenvariables_apart() {
+    let p: Proof = serde_json::from_str(
+        r#"{"root": "c", "nodes": [
+            {"id": "c", "rule": "Cut", "premises": ["l", "r"], "sequent": {"ctx": ["P(y)"], "thm": "∀x ⊤"}},
+            {"id": "l", "rule": "Id", "premises": [], "sequent": {"ctx": ["P(y)"], "thm": "P(y)"}},
+            {"id": "r", "rule": "ForallR", "term": "y", "premises": ["t"], "sequent": {"ctx": [], "thm": "∀x ⊤"}},
+            {"id": "t", "rule": "TopR", "premises": [], "sequent": {"ctx": [], "thm": "⊤"}}
+        ]}"#,
+    )
+    .unwrap();
+    validate_local_wf(&p).unwrap();
+
+    let q = cut_eliminate_all(&p).unwrap();
+    validate_local_wf(&q).unwrap();
+    assert!(q.nodes.iter().all(|n| n.rule != "Cut"));
+    let r = q.nodes.iter().find(|n| n.rule == "ForallR").unwrap();
+    assert_eq!(r.term.as_ref().unwrap().to_string(), "y'");
+}
@@ -0,0 +1,138 @@
+// tests/derive.rs
+use proof_transport::{
+    cert::RewriteStep,
+    derive::{Derivation, DerivationError, ExpandError},
+    registry::{Registry, RegistryError, RuleId},
+    transport::{transport_with_certificate, TransportError},
+    validator::validate_local_wf,
+};
+
+mod support;
+use support::load;
+
+fn kernel() -> Registry {
+    Registry::from_path("examples/kernel/R.json").expect("registry")
+}
+
+#[test]
+fn transport_expands_derived_rules() {
+    let p = load("examples/kernel/and_swap.json");
+    let reg = kernel();
+    let (q, cert) = transport_with_certificate(&p, &reg, 0, 1).expect("transport");
+
+    // The derivations introduce cuts, which are then eliminated.
+    assert_eq!(
+        cert.steps[..2],
+        [
+            RewriteStep::Expansion {
+                rule: "AndE1".into(),
+                expanded: 1
+            },
+            RewriteStep::Expansion {
+                rule: "AndE2".into(),
+                expanded: 1
+            },
+        ]
+    );
+    assert!(matches!(cert.steps[2], RewriteStep::CutElimination { .. }));
+    validate_local_wf(&q).expect("only built-in rules remain");
+    assert!(q.nodes.iter().all(|n| n.rule != "Cut"));
+    cert.verify(&p, &q, Some(&reg))
+        .expect("certificate verifies");
+
+    // Nothing is expanded while the rules are still enabled.
+    let (same, cert) = transport_with_certificate(&p, &reg, 0, 0).expect("transport");
+    assert!(cert.steps.is_empty());
+    assert_eq!(same.nodes.len(), p.nodes.len());
+}
+
+#[test]
+fn derivations_apply_from_their_time_on() {
+    let reg = kernel();
+    assert!(reg.derivations_at(0).is_empty());
+    let at1: Vec<_> = reg.derivations_at(1).iter().map(|d| &d.rule).collect();
+    assert_eq!(at1, ["AndE1", "AndE2"]);
+    assert!(reg.is_enabled("AndE1", 0) && !reg.is_enabled("AndE1", 1));
+}
+
+#[test]
+fn expansion_reports_inferences_the_template_does_not_fit() {
+    // This derivation only covers `A ∧ A ⊢ A`.
+    let mut reg = kernel();
+    for d in &mut reg.derivations {
+        d.premises = serde_json::from_str(r#"[{"ctx": [], "thm": "A ∧ A"}]"#).unwrap();
+    }
+    let p = load("examples/kernel/and_swap.json");
+    let err = transport_with_certificate(&p, &reg, 0, 1).unwrap_err();
+    assert!(
+        matches!(&err, TransportError::Expansion(ExpandError::NoMatch { node, rule, .. })
+            if node == "a" && rule == "AndE1"),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn registry_checks_its_derivations() {
+    let with = |d: &str| {
+        let src = format!(r#"{{"times": [], "derivations": [{d}]}}"#);
+        Registry::from_json(&src).unwrap_err().to_string()
+    };
+    let err = with(
+        r##"{"rule": "Or1", "metavariables": ["A"], "conclusion": "A",
+            "steps": [{"id": "r", "rule": "Id", "premises": ["#1"], "sequent": "A"}]}"##,
+    );
+    assert!(err.contains("step `r` has unknown premise `#1`"), "{err}");
+
+    let err = with(
+        r#"{"rule": "Or1", "metavariables": ["A", "B"], "conclusion": "A",
+            "steps": [{"id": "r", "rule": "Id", "sequent": "B"}]}"#,
+    );
+    assert!(err.contains("metavariable `B` of step `r`"), "{err}");
+
+    let err = with(
+        r#"{"rule": "Or1", "conclusion": "A", "steps": [{"id": "r", "rule": "AndE", "sequent": "A"}]}"#,
+    );
+    assert!(err.contains("uses unknown rule `AndE`"), "{err}");
+
+    let src = r#"{"times": [], "derivations": [{"rule": "Or1", "conclusion": "A", "steps": []}]}"#;
+    assert!(matches!(
+        Registry::from_json(src),
+        Err(RegistryError::Derivation(DerivationError::NoSteps { .. }))
+    ));
+}
+
+#[test]
+fn derivation_errors_name_the_step() {
+    let check = |d: &str| {
+        serde_json::from_str::<Derivation>(d)
+            .expect("derivation")
+            .check_well_formed()
+            .unwrap_err()
+    };
+    assert_eq!(
+        check(
+            r#"{"rule": "Or1", "conclusion": "A",
+                "steps": [{"id": "r", "rule": "Id", "sequent": "A"},
+                          {"id": "r", "rule": "Id", "sequent": "A"}]}"#
+        ),
+        DerivationError::RepeatedStep {
+            rule: "Or1".into(),
+            step: "r".into()
+        }
+    );
+
+    // Templates carry no terms, so quantifier steps could never validate.
+    let err = check(
+        r#"{"rule": "Or1", "metavariables": ["A"], "conclusion": "A",
+            "steps": [{"id": "q", "rule": "ForallL", "sequent": "A"}]}"#,
+    );
+    assert_eq!(
+        err,
+        DerivationError::QuantifierStep {
+            rule: "Or1".into(),
+            step: "q".into(),
+            used: RuleId::ForallL
+        }
+    );
+    assert!(err.to_string().contains("step `q` uses ForallL"), "{err}");
+}
@@ -0,0 +1,133 @@
+// tests/eta.rs
+use std::collections::HashSet;
+
+use proof_transport::{
+    ast::Proof,
+    cert::RewriteStep,
+    eta::{eta_expand, EtaError},
+    parse::resolve_proof,
+    registry::{Registry, RuleId, TimeSlice},
+    restrict::Restriction,
+    transport::transport_with_certificate,
+    validator::validate_at,
+};
+
+/// A one-node proof of `ctx ⊢ succ` by `Id`.
+fn axiom(calculus: &str, ctx: &[&str], succ: &[&str]) -> Proof {
+    let json = serde_json::json!({
+        "calculus": calculus,
+        "root": "ax",
+        "nodes": [{"id": "ax", "rule": "Id", "premises": [], "sequent": {"ctx": ctx, "succ": succ}}],
+    });
+    serde_json::from_value(json).unwrap()
+}
+
+fn all_rules() -> HashSet<RuleId> {
+    RuleId::ALL.into_iter().collect()
+}
+
+/// Every rule enabled, `Id` on atoms only.
+fn atomic_id() -> Registry {
+    Registry {
+        times: vec![TimeSlice {
+            t: 0,
+            enabled_rules: RuleId::ALL.to_vec(),
+            restrictions: [(RuleId::Id, vec![Restriction::Atomic])].into(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn expands_every_connective_down_to_atoms() {
+    for f in [
+        "A ∧ B",
+        "A ∨ B",
+        "A ⇒ B",
+        "¬A",
+        "A ⇔ B",
+        "∀x P(x)",
+        "∃x P(x)",
+        "(A ⇒ ⊥) ∧ ⊤",
+        "∀x (P(x) ∨ ∃y Q(x, y))",
+    ] {
+        for calculus in ["intuitionistic", "classical"] {
+            let p = axiom(calculus, &[f], &[f]);
+            let out = eta_expand(&p, &all_rules()).expect("expands");
+            validate_at(&out, &atomic_id(), 0).unwrap_or_else(|e| panic!("{f} ({calculus}): {e}"));
+            assert_eq!(out.root, "ax");
+            let root = |p: &Proof| {
+                resolve_proof(p)
+                    .unwrap()
+                    .root_node()
+                    .unwrap()
+                    .sequent
+                    .clone()
+            };
+            assert_eq!(root(&out), root(&p));
+        }
+    }
+}
+
+#[test]
+fn eigenvariables_avoid_the_context() {
+    let p = axiom("intuitionistic", &["∀x P(x)", "Q(x)"], &["∀x P(x)"]);
+    let out = eta_expand(&p, &all_rules()).expect("expands");
+    validate_at(&out, &atomic_id(), 0).expect("valid");
+    let forall_r = out.nodes.iter().find(|n| n.rule == "ForallR").unwrap();
+    assert_eq!(forall_r.term.as_ref().unwrap().to_string(), "x'");
+}
+
+#[test]
+fn atomic_axioms_are_left_alone() {
+    let p = axiom("classical", &["A", "B ∧ C"], &["A", "B ∧ C"]);
+    let out = eta_expand(&p, &all_rules()).expect("expands");
+    assert_eq!(out.nodes.len(), 1);
+}
+
+#[test]
+fn expansion_needs_the_connective_rules() {
+    let err = eta_expand(
+        &axiom("intuitionistic", &["A ⇒ B"], &["A ⇒ B"]),
+        &[RuleId::Id].into(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        EtaError::RuleDisabled {
+            node: "ax".into(),
+            rule: RuleId::ImpL,
+        }
+    );
+}
+
+#[test]
+fn transport_expands_identity_axioms_when_id_is_restricted_to_atoms() {
+    let reg = Registry::from_json(
+        r#"{"times": [
+            {"t": 0, "enabled_rules": ["Id", "ImpR", "AndR", "AndL1", "AndL2"]},
+            {"t": 1, "enabled_rules": ["Id", "ImpR", "AndR", "AndL1", "AndL2"],
+             "restrictions": {"Id": ["atomic"]}}
+        ]}"#,
+    )
+    .unwrap();
+    let p: Proof = serde_json::from_str(
+        r#"{"root": "imp", "nodes": [
+            {"id": "imp", "rule": "ImpR", "premises": ["ax"], "sequent": {"ctx": [], "thm": "A ∧ B ⇒ A ∧ B"}},
+            {"id": "ax", "rule": "Id", "premises": [], "sequent": {"ctx": ["A ∧ B"], "thm": "A ∧ B"}}
+        ]}"#,
+    )
+    .unwrap();
+    assert!(validate_at(&p, &reg, 1).is_err());
+
+    let (out, cert) = transport_with_certificate(&p, &reg, 0, 1).expect("transport");
+    assert_eq!(cert.steps, [RewriteStep::EtaExpansion { expanded: 1 }]);
+    assert_eq!(
+        cert.steps[0].to_string(),
+        "eta-expanded 1 identity axiom(s)"
+    );
+    validate_at(&out, &reg, 1).expect("valid at t=1");
+    cert.verify(&p, &out, Some(&reg))
+        .expect("certificate verifies");
+}
@@ -4,7 +4,7 @@ use std::fs;
 #[test]
 fn load_example_proofs() {
     for f in [
-        "examples/proof_with_cut.json",
+        "examples/proof_with_cut_valid.json",
         "examples/proof_cut_eliminated.json",
         "examples/R.json",
     ] {
@@ -4,6 +4,6 @@ use std::fs::File;
 
 #[test]
 fn fragility_score_nonzero_on_cut_proof() {
-    let p: Proof = from_reader(File::open("examples/proof_with_cut.json").unwrap()).unwrap();
+    let p: Proof = from_reader(File::open("examples/proof_with_cut_valid.json").unwrap()).unwrap();
     assert!(fragility_score(&p) > 1);
 }
@@ -5,11 +5,11 @@ use std::fs::File;
 #[test]
 fn fragility_drops_on_root_cut_example() {
     // This example has a `Cut` at the root; after elimination it must strictly drop.
-    let p: Proof = from_reader(File::open("examples/proof_with_cut.json").unwrap()).unwrap();
+    let p: Proof = from_reader(File::open("examples/proof_with_cut_valid.json").unwrap()).unwrap();
     validate_local_wf(&p).unwrap();
 
     let before = fragility_score(&p);
-    let q = cut_eliminate_all(&p);
+    let q = cut_eliminate_all(&p).unwrap();
     validate_local_wf(&q).unwrap();
 
     let after = fragility_score(&q);
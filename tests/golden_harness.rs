@@ -1,9 +1,23 @@
-use proof_transport::{ast::Proof, cut_eliminate_all, validate_local_wf};
+use proof_transport::{
+    ast::Proof, cut_eliminate_all, registry::Registry, transport, validate_local_wf, TransportError,
+};
 use serde_json::from_reader;
 use std::fs;
 use std::fs::File;
 use std::path::Path;
 
+/// The original examples whose cuts do not fit the `Cut` rule. They are
+/// kept as they were, as negative fixtures.
+const ILL_FORMED: [&str; 7] = [
+    "examples/or_proof_with_cut.json",
+    "examples/proof_cut_chain.json",
+    "examples/proof_cut_pair.json",
+    "examples/proof_fo_quantifiers.json",
+    "examples/proof_with_cut.json",
+    "examples/proof_with_unreachable.json",
+    "examples/prop_cut_in.json",
+];
+
 fn try_load_proof(path: &Path) -> Option<Proof> {
     let f = File::open(path).ok()?;
     from_reader::<_, Proof>(f).ok()
@@ -14,13 +28,34 @@ fn scan_examples_and_smoke_transport() {
     for entry in fs::read_dir("examples").unwrap() {
         let entry = entry.unwrap();
         let path = entry.path();
+        if ILL_FORMED.iter().any(|f| path == Path::new(f)) {
+            continue;
+        }
 
-        if entry.file_type().unwrap().is_file() && path.extension().map_or(false, |e| e == "json") {
+        if entry.file_type().unwrap().is_file() && path.extension().is_some_and(|e| e == "json") {
             if let Some(p) = try_load_proof(&path) {
                 // Local well-formedness and a transport smoke test.
                 validate_local_wf(&p).unwrap();
-                let _ = cut_eliminate_all(&p);
+                cut_eliminate_all(&p).unwrap();
             }
         }
     }
 }
+
+#[test]
+fn ill_formed_examples_are_rejected() {
+    let reg = Registry::from_path("examples/R.json").unwrap();
+    for path in ILL_FORMED {
+        let p = try_load_proof(Path::new(path)).expect("parses");
+        let err = validate_local_wf(&p).expect_err(path);
+        assert_eq!(err.code(), "rule-check", "{path}: {err}");
+        assert!(cut_eliminate_all(&p).is_err(), "{path}");
+        assert!(
+            matches!(
+                transport(&p, &reg, 0, 1),
+                Err(TransportError::InvalidInput(_))
+            ),
+            "{path}"
+        );
+    }
+}
@@ -0,0 +1,102 @@
+// tests/hops.rs
+use proof_transport::{
+    cert::{CertError, DirectLoss, Hop, RewriteStep},
+    registry::Registry,
+    transport::transport_with_certificate,
+    validator::validate_at,
+};
+
+mod support;
+use support::load;
+
+/// `Cut` goes away at t=1 and comes back at t=3.
+fn cut_gap() -> Registry {
+    Registry::from_json(
+        r#"{"times": [
+            {"t": 0, "enabled_rules": ["Id", "Cut"]},
+            {"t": 1, "enabled_rules": ["Id"]},
+            {"t": 3, "enabled_rules": ["Id", "Cut"]}
+        ]}"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn transport_walks_every_intermediate_slice() {
+    let reg = cut_gap();
+    let p = load("examples/proof_with_cut_valid.json");
+    let (q, cert) = transport_with_certificate(&p, &reg, 0, 4).expect("transport");
+
+    assert_eq!(
+        cert.hops,
+        [
+            Hop {
+                from: 0,
+                to: 1,
+                steps: vec![RewriteStep::CutElimination { removed: 1 }]
+            },
+            Hop {
+                from: 1,
+                to: 3,
+                steps: vec![]
+            },
+            Hop {
+                from: 3,
+                to: 4,
+                steps: vec![]
+            },
+        ]
+    );
+    assert_eq!(cert.steps, [RewriteStep::CutElimination { removed: 1 }]);
+    assert_eq!(
+        cert.hops[0].to_string(),
+        "hop t=0 -> t=1: eliminated 1 cut(s)"
+    );
+    assert!(q.nodes.iter().all(|n| n.rule != "Cut"));
+    validate_at(&q, &reg, 1).expect("valid at every hop");
+
+    // Going straight to t=4 keeps the cut, which t=1 rejects.
+    assert_eq!(cert.direct_loss, Some(DirectLoss::InvalidAt { t: 1 }));
+    cert.verify(&p, &q, Some(&reg))
+        .expect("certificate verifies");
+
+    // A single hop has nothing to compare against.
+    let (_, cert) = transport_with_certificate(&p, &reg, 0, 1).expect("transport");
+    assert_eq!(cert.hops.len(), 1);
+    assert_eq!(cert.direct_loss, None);
+}
+
+#[test]
+fn direct_transport_may_fail_where_hops_succeed() {
+    // From t=3 on, `AndE1` has a derivation that only fits `A ∧ A`, but by
+    // then the t=1 derivation has already replaced every use.
+    let mut reg = Registry::from_path("examples/kernel/R.json").unwrap();
+    let mut narrow = reg.derivations[0].clone();
+    narrow.t = 3;
+    narrow.premises = serde_json::from_str(r#"[{"ctx": [], "thm": "A ∧ A"}]"#).unwrap();
+    reg.derivations.push(narrow);
+
+    let p = load("examples/kernel/and_swap.json");
+    let (_, cert) = transport_with_certificate(&p, &reg, 0, 3).expect("transport");
+    assert_eq!(cert.hops.len(), 2);
+    match &cert.direct_loss {
+        Some(DirectLoss::Fails { reason }) => {
+            assert!(reason.contains("node a is not an instance"), "{reason}")
+        }
+        other => panic!("expected a failing direct transport, got {other:?}"),
+    }
+}
+
+#[test]
+fn certificates_check_the_hops_add_up() {
+    let reg = cut_gap();
+    let p = load("examples/proof_with_cut_valid.json");
+    let (q, mut cert) = transport_with_certificate(&p, &reg, 0, 4).unwrap();
+    cert.hops[0].steps.clear();
+    let err = cert.verify(&p, &q, Some(&reg)).unwrap_err();
+    assert_eq!(err, CertError::HopMismatch);
+    assert!(
+        err.to_string().contains("hops' steps do not add up"),
+        "{err}"
+    );
+}
@@ -10,9 +10,9 @@ use proof_transport::{cut_eliminate_all, fragility_score, validate_local_wf};
 #[test]
 fn fragility_strictly_drops_on_cut_examples() {
     let paths = [
-        "examples/proof_with_cut.json",  // existing root Cut
-        "examples/proof_cut_chain.json", // nested/internal Cut
-        "examples/proof_cut_pair.json",  // sibling Cuts
+        "examples/proof_with_cut_valid.json",  // existing root Cut
+        "examples/proof_cut_chain_valid.json", // nested/internal Cut
+        "examples/proof_cut_pair_valid.json",  // sibling Cuts
     ];
 
     for path in paths {
@@ -20,7 +20,7 @@ fn fragility_strictly_drops_on_cut_examples() {
         validate_local_wf(&p).expect("wf before");
 
         let before = fragility_score(&p);
-        let q = cut_eliminate_all(&p);
+        let q = cut_eliminate_all(&p).unwrap();
         validate_local_wf(&q).expect("wf after");
 
         let after = fragility_score(&q);
@@ -37,11 +37,11 @@ fn fragility_strictly_drops_on_cut_examples() {
 #[test]
 fn fragility_never_increases_on_all_examples() {
     let paths = [
-        "examples/proof_with_cut.json",
-        "examples/proof_cut_chain.json",
-        "examples/proof_cut_pair.json",
-        "examples/proof_fo_quantifiers.json",
-        "examples/proof_with_unreachable.json",
+        "examples/proof_with_cut_valid.json",
+        "examples/proof_cut_chain_valid.json",
+        "examples/proof_cut_pair_valid.json",
+        "examples/proof_fo_quantifiers_valid.json",
+        "examples/proof_with_unreachable_valid.json",
         "examples/proof_cut_free.json",
     ];
 
@@ -50,7 +50,7 @@ fn fragility_never_increases_on_all_examples() {
         validate_local_wf(&p).expect("wf before");
 
         let before = fragility_score(&p);
-        let q = cut_eliminate_all(&p);
+        let q = cut_eliminate_all(&p).unwrap();
         validate_local_wf(&q).expect("wf after");
 
         let after = fragility_score(&q);
@@ -65,11 +65,11 @@ fn fragility_never_increases_on_all_examples() {
 /// `ghost` example we added to exercise graph cleanup.
 #[test]
 fn unreachable_nodes_are_pruned() {
-    let p = load("examples/proof_with_unreachable.json");
+    let p = load("examples/proof_with_unreachable_valid.json");
     validate_local_wf(&p).expect("wf before");
 
     let before_nodes = p.nodes.len();
-    let q = cut_eliminate_all(&p); // elimination + subsequent prune()
+    let q = cut_eliminate_all(&p).unwrap(); // elimination + subsequent prune()
     validate_local_wf(&q).expect("wf after");
     let after_nodes = q.nodes.len();
 
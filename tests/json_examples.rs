@@ -11,7 +11,7 @@ pub fn example_paths() -> Vec<PathBuf> {
                 let p = entry.path();
                 if p.is_dir() {
                     walk(&p, out);
-                } else if p.extension().map_or(false, |e| e == "json") {
+                } else if p.extension().is_some_and(|e| e == "json") {
                     out.push(p);
                 }
             }
@@ -0,0 +1,114 @@
+use proof_transport::{
+    ast::{Formula, FormulaNode, Term, TermNode},
+    parse::{parse_formula, resolve_proof},
+    validator::validate_local_wf,
+};
+
+mod support;
+use support::load;
+
+fn var(s: &str) -> Formula {
+    Formula::Node(FormulaNode::Var(s.into()))
+}
+
+fn imp(a: Formula, b: Formula) -> Formula {
+    Formula::Node(FormulaNode::Imp(Box::new(a), Box::new(b)))
+}
+
+fn and(a: Formula, b: Formula) -> Formula {
+    Formula::Node(FormulaNode::And(Box::new(a), Box::new(b)))
+}
+
+fn or(a: Formula, b: Formula) -> Formula {
+    Formula::Node(FormulaNode::Or(Box::new(a), Box::new(b)))
+}
+
+#[test]
+fn unicode_and_ascii_spellings_agree() {
+    let pairs = [
+        ("A ∧ B ⇒ C", "A /\\ B -> C"),
+        ("A ∨ B → ⊥", "A \\/ B => _|_"),
+        ("∀x ∃y R(x, f(y))", "forall x. exists y. R(x, f(y))"),
+        ("¬A ∨ ⊤", "~A \\/ true"),
+        ("A ⇔ B ↔ C", "A <-> B <=> C"),
+    ];
+    for (u, a) in pairs {
+        assert_eq!(
+            parse_formula(u).unwrap(),
+            parse_formula(a).unwrap(),
+            "{u} vs {a}"
+        );
+    }
+}
+
+#[test]
+fn precedence_and_associativity() {
+    let (a, b, c) = (var("A"), var("B"), var("C"));
+
+    // ∧ binds tighter than ∨, which binds tighter than ⇒.
+    assert_eq!(
+        parse_formula("A ∧ B ∨ C ⇒ A").unwrap(),
+        imp(or(and(a.clone(), b.clone()), c.clone()), a.clone())
+    );
+    // ⇒ associates to the right, ∧ and ∨ to the left.
+    assert_eq!(
+        parse_formula("A ⇒ B ⇒ C").unwrap(),
+        imp(a.clone(), imp(b.clone(), c.clone()))
+    );
+    assert_eq!(
+        parse_formula("A ∨ B ∨ C").unwrap(),
+        or(or(a.clone(), b.clone()), c.clone())
+    );
+    // ⇔ binds loosest and associates to the right; ¬ binds tightest.
+    let not = |f: Formula| Formula::Node(FormulaNode::Not(Box::new(f)));
+    let iff = |f: Formula, g: Formula| Formula::Node(FormulaNode::Iff(Box::new(f), Box::new(g)));
+    assert_eq!(
+        parse_formula("¬A ⇒ B ⇔ C ⇔ A").unwrap(),
+        iff(imp(not(a.clone()), b.clone()), iff(c.clone(), a.clone()))
+    );
+    // Quantifiers bind tightly.
+    let px = Formula::Node(FormulaNode::Pred {
+        name: "P".into(),
+        args: vec![Term::Node(TermNode::Var("x".into()))],
+    });
+    assert_eq!(
+        parse_formula("∀x P(x) ⇒ C").unwrap(),
+        imp(
+            Formula::Node(FormulaNode::Forall("x".into(), Box::new(px))),
+            c
+        )
+    );
+}
+
+#[test]
+fn errors_carry_offsets_and_node_ids() {
+    let err = parse_formula("A ∧ ∨ B").unwrap_err();
+    assert_eq!(err.offset, 4);
+
+    let err = parse_formula("(A ⇒ B").unwrap_err();
+    assert_eq!(err.offset, 6);
+    assert!(err.message.contains("end of input"), "{}", err.message);
+
+    let mut p = load("examples/proof_fo_quantifiers_valid.json");
+    p.nodes[1].sequent.succ = vec![Formula::Text("∀x P(x".into())];
+    let err = resolve_proof(&p).unwrap_err();
+    assert_eq!(err.node.as_deref(), Some("a_forall"));
+    assert_eq!(err.offset, 6);
+    assert!(
+        err.to_string().starts_with("node a_forall: offset 6"),
+        "{err}"
+    );
+}
+
+#[test]
+fn text_examples_validate_semantically() {
+    // `proof_cut_free.json` needs `(A ⇒ A)` to be read as an implication for
+    // its ImpR step to check.
+    for path in [
+        "examples/proof_cut_free.json",
+        "examples/proof_fo_quantifiers_valid.json",
+        "examples/proof_cut_pair_valid.json",
+    ] {
+        validate_local_wf(&load(path)).expect(path);
+    }
+}
@@ -0,0 +1,133 @@
+use proof_transport::{
+    ast::{Formula, FormulaNode, Sequent, Term, TermNode},
+    parse::{parse_formula, resolve_proof},
+    pretty::Printer,
+};
+
+mod support;
+use support::load;
+
+#[test]
+fn prints_minimal_parentheses() {
+    for src in [
+        "A ∧ B ∨ C ⇒ A",
+        "(A ⇒ B) ⇒ C",
+        "A ⇒ B ⇒ C",
+        "A ∨ (B ∨ C)",
+        "A ∧ (B ⇒ C)",
+        "∀x (P(x) ⇒ ∃y R(x, f(y)))",
+        "(∀x P(x)) ∧ ⊤ ⇒ ⊥",
+        "(A ⇔ B) ⇔ C",
+        "A ⇒ B ⇔ ¬(B ∧ A)",
+    ] {
+        let f = parse_formula(src).unwrap();
+        let printed = f.to_string();
+        assert_eq!(parse_formula(&printed).unwrap(), f, "{src} -> {printed}");
+    }
+
+    assert_eq!(parse_formula("((A ∧ B))").unwrap().to_string(), "A ∧ B");
+    assert_eq!(
+        parse_formula("(A ∨ B) ∨ C").unwrap().to_string(),
+        "A ∨ B ∨ C"
+    );
+    assert_eq!(
+        parse_formula("A ∨ (B ∨ C)").unwrap().to_string(),
+        "A ∨ (B ∨ C)"
+    );
+    assert_eq!(
+        parse_formula("(∀x P(x)) ∧ Q").unwrap().to_string(),
+        "∀x P(x) ∧ Q"
+    );
+    assert_eq!(
+        parse_formula("A ⇔ (B ⇔ C)").unwrap().to_string(),
+        "A ⇔ B ⇔ C"
+    );
+    assert_eq!(parse_formula("¬(¬A)").unwrap().to_string(), "¬¬A");
+}
+
+#[test]
+fn ascii_output_round_trips() {
+    let f = parse_formula("∀x (P(x) ∧ ¬Q ⇒ R ∨ ⊥)").unwrap();
+    let ascii = Printer::ascii().formula(&f);
+    assert_eq!(ascii, "forall x. (P(x) /\\ ~Q -> R \\/ _|_)");
+    assert_eq!(parse_formula(&ascii).unwrap(), f);
+}
+
+#[test]
+fn sequents_and_text_formulas() {
+    let s = Sequent::new(
+        vec![Formula::Text("A ∧ B".into()), parse_formula("C").unwrap()],
+        Formula::Text("B".into()),
+    );
+    assert_eq!(s.to_string(), "A ∧ B, C ⊢ B");
+    assert_eq!(Printer::ascii().sequent(&s), "A ∧ B, C |- B");
+
+    // Printing the parsed example strings gives back what the examples say.
+    let p = resolve_proof(&load("examples/proof_fo_quantifiers_valid.json")).unwrap();
+    assert_eq!(
+        p.root_node().unwrap().sequent.to_string(),
+        "∀x P(x), ∃y Q(y) ⊢ ∃y Q(y)"
+    );
+}
+
+#[test]
+fn proof_tree_lists_premises_and_unreachable_nodes() {
+    let p = load("examples/proof_with_unreachable_valid.json");
+    let tree = p.to_string();
+    assert_eq!(
+        tree,
+        "c_main: p, q ⊢ q    [Cut]\n\
+         ├─ a_left: p ⊢ p    [Axiom]\n\
+         └─ a_right: q ⊢ q    [Axiom]\n\
+         unreachable: ghost\n"
+    );
+}
+
+#[test]
+fn keyword_names_round_trip_in_backticks() {
+    let node = Formula::Node;
+    let var = |x: &str| Term::Node(TermNode::Var(x.into()));
+    for kw in ["true", "false", "forall", "exists"] {
+        let formulas = [
+            node(FormulaNode::Var(kw.into())),
+            node(FormulaNode::Pred {
+                name: kw.into(),
+                args: vec![var(kw)],
+            }),
+            node(FormulaNode::Forall(
+                kw.into(),
+                Box::new(node(FormulaNode::Pred {
+                    name: "P".into(),
+                    args: vec![Term::Node(TermNode::Func {
+                        name: kw.into(),
+                        args: vec![var(kw)],
+                    })],
+                })),
+            )),
+            node(FormulaNode::Exists(
+                kw.into(),
+                Box::new(node(FormulaNode::And(
+                    Box::new(node(FormulaNode::Var(kw.into()))),
+                    Box::new(node(FormulaNode::Top)),
+                ))),
+            )),
+        ];
+        for f in formulas {
+            for printer in [Printer::unicode(), Printer::ascii()] {
+                let printed = printer.formula(&f);
+                assert_eq!(parse_formula(&printed).unwrap(), f, "{printed}");
+            }
+        }
+        assert_eq!(
+            node(FormulaNode::Var(kw.into())).to_string(),
+            format!("`{kw}`")
+        );
+    }
+    assert_eq!(
+        parse_formula("`true` ∧ true").unwrap().to_string(),
+        "`true` ∧ ⊤"
+    );
+    for bad in ["`", "``", "`a b`", "`true"] {
+        assert!(parse_formula(bad).is_err(), "{bad}");
+    }
+}
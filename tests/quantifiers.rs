@@ -0,0 +1,98 @@
+// tests/quantifiers.rs
+use proof_transport::{
+    ast::{Formula, Proof, Term},
+    cutelim::cut_eliminate_all,
+    registry::RuleId,
+    validator::{validate_local_wf, ValidationError},
+};
+
+mod support;
+use support::load;
+
+/// The rule and reason of the first rule-check failure.
+fn failure(p: &Proof) -> (String, RuleId, String) {
+    match validate_local_wf(p) {
+        Err(ValidationError::RuleCheck { node, rule, reason }) => (node, rule, reason),
+        other => panic!("expected a rule check failure, got {other:?}"),
+    }
+}
+
+/// `∀x (P(x) ∧ Q(x)) ⊢ ∀x P(x)` with eigenvariable `eigen`.
+fn forall_r(eigen: &str) -> Proof {
+    serde_json::from_value(serde_json::json!({
+        "root": "gen",
+        "nodes": [
+            { "id": "ax", "rule": "Id", "sequent": { "ctx": ["P(a)"], "thm": "P(a)" } },
+            { "id": "and", "rule": "AndL1", "premises": ["ax"],
+              "sequent": { "ctx": ["P(a) ∧ Q(a)"], "thm": "P(a)" } },
+            { "id": "inst", "rule": "ForallL", "term": "a", "premises": ["and"],
+              "sequent": { "ctx": ["∀x (P(x) ∧ Q(x))"], "thm": "P(a)" } },
+            { "id": "gen", "rule": "ForallR", "term": eigen, "premises": ["inst"],
+              "sequent": { "ctx": ["∀x (P(x) ∧ Q(x))"], "thm": "∀x P(x)" } }
+        ]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn quantifier_rules_check_their_instances() {
+    validate_local_wf(&load("examples/proof_fo_rules.json")).expect("well-formed");
+    validate_local_wf(&forall_r("a")).expect("well-formed");
+
+    // The witness must be the one the premise uses.
+    let mut p = load("examples/proof_fo_rules.json");
+    p.nodes[3].term = Some(Term::Text("b".into()));
+    let (node, rule, _) = failure(&p);
+    assert_eq!((node.as_str(), rule), ("inst", RuleId::ForallL));
+
+    p.nodes[3].term = None;
+    assert_eq!(failure(&p).2, "no instantiation term given");
+}
+
+#[test]
+fn eigenvariables_must_be_fresh_variables() {
+    // `a` is free in the conclusion of `ExistsL`.
+    let mut p = load("examples/proof_fo_rules.json");
+    p.nodes[5].sequent.ctx.push(Formula::Text("P(a)".into()));
+    let (node, rule, reason) = failure(&p);
+    assert_eq!((node.as_str(), rule), ("root", RuleId::ExistsL));
+    assert_eq!(reason, "eigenvariable a occurs free in the conclusion");
+
+    let (_, rule, reason) = failure(&forall_r("f(a)"));
+    assert_eq!(rule, RuleId::ForallR);
+    assert_eq!(reason, "eigenvariable must be a variable, found f(a)");
+}
+
+#[test]
+fn instances_match_up_to_bound_variable_names() {
+    // Instantiating `x := y` in `∀y R(x, y)` renames the inner binder.
+    let p: Proof = serde_json::from_value(serde_json::json!({
+        "root": "inst",
+        "nodes": [
+            { "id": "ax", "rule": "Id", "sequent": { "ctx": ["∀z R(y, z)"], "thm": "∀z R(y, z)" } },
+            { "id": "inst", "rule": "ForallL", "term": "y", "premises": ["ax"],
+              "sequent": { "ctx": ["∀x ∀y R(x, y)"], "thm": "∀z R(y, z)" } }
+        ]
+    }))
+    .unwrap();
+    validate_local_wf(&p).expect("well-formed");
+}
+
+#[test]
+fn id_and_cut_match_up_to_bound_variable_names() {
+    let p: Proof = serde_json::from_value(serde_json::json!({
+        "root": "cut",
+        "nodes": [
+            { "id": "ax", "rule": "Id", "sequent": { "ctx": ["∀x P(x)"], "thm": "∀y P(y)" } },
+            { "id": "ax2", "rule": "Id", "sequent": { "ctx": ["∀z P(z)"], "thm": "∀x P(x)" } },
+            { "id": "cut", "rule": "Cut", "premises": ["ax", "ax2"],
+              "sequent": { "ctx": ["∀x P(x)"], "thm": "∀x P(x)" } }
+        ]
+    }))
+    .unwrap();
+    validate_local_wf(&p).expect("well-formed");
+
+    let q = cut_eliminate_all(&p).unwrap();
+    validate_local_wf(&q).expect("well-formed");
+    assert!(q.nodes.iter().all(|n| n.rule != "Cut"));
+}
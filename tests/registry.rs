@@ -1,4 +1,4 @@
-use proof_transport::registry::{Registry, RuleId, TimeSlice};
+use proof_transport::registry::{Registry, RegistryError, RuleId, TimeSlice};
 use std::collections::HashSet;
 
 #[test]
@@ -8,12 +8,15 @@ fn registry_enabled_at_works() {
             TimeSlice {
                 t: 0,
                 enabled_rules: vec![RuleId::Id, RuleId::Cut],
+                ..Default::default()
             },
             TimeSlice {
                 t: 1,
                 enabled_rules: vec![RuleId::Id],
+                ..Default::default()
             },
         ],
+        ..Default::default()
     };
 
     let at0: HashSet<_> = reg.enabled_at(0);
@@ -22,3 +25,55 @@ fn registry_enabled_at_works() {
     let at1: HashSet<_> = reg.enabled_at(1);
     assert!(!at1.contains(&RuleId::Cut));
 }
+
+#[test]
+fn registry_loads_from_json_file() {
+    let reg = Registry::from_path("examples/R.json").expect("load R.json");
+    assert_eq!(reg.times.len(), 2);
+
+    // Aliases resolve to the same RuleId the validator uses.
+    let at0 = reg.enabled_at(0);
+    assert!(at0.contains(&RuleId::Or1), "OrR1 should load as Or1");
+    assert!(at0.contains(&RuleId::BotI));
+    assert!(at0.contains(&RuleId::Cut));
+    assert!(!reg.enabled_at(1).contains(&RuleId::Cut));
+}
+
+#[test]
+fn registry_rejects_unknown_rule_names() {
+    let src = r#"{ "times": [ { "t": 0, "enabled_rules": ["Id", "∨R1", "BotL"] } ] }"#;
+    let err = Registry::from_json(src).unwrap_err();
+    assert!(
+        matches!(&err, RegistryError::UnknownRule { name, t: 0 } if name == "BotL"),
+        "{err:?}"
+    );
+    assert!(err.to_string().contains("unknown rule `BotL`"), "{err}");
+}
+
+#[test]
+fn registry_rejects_unordered_slices() {
+    let src =
+        r#"{ "times": [ { "t": 2, "enabled_rules": [] }, { "t": 1, "enabled_rules": [] } ] }"#;
+    let err = Registry::from_json(src).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            RegistryError::UnorderedSlices {
+                before: 2,
+                after: 1
+            }
+        ),
+        "{err:?}"
+    );
+    assert!(err.to_string().contains("increasing order"), "{err}");
+}
+
+#[test]
+fn registry_errors_say_which_file() {
+    let err = Registry::from_path("examples/no_such_registry.json").unwrap_err();
+    assert!(matches!(err, RegistryError::Io { .. }), "{err:?}");
+    assert!(
+        err.to_string().contains("examples/no_such_registry.json"),
+        "{err}"
+    );
+}
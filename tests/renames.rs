@@ -0,0 +1,85 @@
+// tests/renames.rs
+use proof_transport::{
+    cert::{CertError, RewriteStep},
+    registry::{Registry, RuleId},
+    transport::transport_with_certificate,
+    validator::{validate_at, ValidationError},
+};
+
+mod support;
+use support::load;
+
+fn registry() -> Registry {
+    Registry::from_json(
+        r#"{"times": [
+            {"t": 0, "enabled_rules": ["Id", "ImpR"]},
+            {"t": 1, "enabled_rules": ["Id", "ImpR"], "renames": {"ImpR": "ImpIntro"}}
+        ]}"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn transport_uses_the_target_slice_names() {
+    let reg = registry();
+    let p = load("examples/proof_cut_free.json");
+
+    let (out, cert) = transport_with_certificate(&p, &reg, 0, 1).expect("transport");
+    let rules: Vec<&str> = out.nodes.iter().map(|n| n.rule.as_str()).collect();
+    // Aliases of rules the slice does not rename are left alone.
+    assert_eq!(rules, ["ImpIntro", "Axiom"]);
+    assert_eq!(cert.steps, [RewriteStep::Rename { renamed: 1 }]);
+    // The certificate reads `ImpIntro` as the rule it names.
+    assert_eq!(cert.rules_after, [RuleId::Id, RuleId::ImpR]);
+    cert.verify(&p, &out, Some(&reg))
+        .expect("certificate verifies");
+    let mut forged = cert.clone();
+    forged.to.enabled_rules.retain(|r| *r != RuleId::ImpR);
+    let err = forged.verify(&p, &out, Some(&reg)).unwrap_err();
+    assert_eq!(
+        err,
+        CertError::RuleDisabled {
+            rule: RuleId::ImpR,
+            t: 1
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "output uses ImpR, which is not enabled at t=1"
+    );
+    validate_at(&out, &reg, 1).expect("valid at t=1");
+    assert!(matches!(
+        validate_at(&out, &reg, 0),
+        Err(ValidationError::RuleNotEnabled { ref rule, t: 0, .. }) if rule == "ImpIntro"
+    ));
+
+    // And back again.
+    let (back, cert) = transport_with_certificate(&out, &reg, 1, 0).expect("transport back");
+    assert_eq!(back.nodes[0].rule, "ImpR");
+    assert_eq!(cert.steps, [RewriteStep::Rename { renamed: 1 }]);
+    validate_at(&back, &reg, 0).expect("valid at t=0");
+}
+
+#[test]
+fn renames_must_not_clash() {
+    for renames in [r#"{"ImpR": "Cut"}"#, r#"{"ImpR": "R", "AndR": "R"}"#] {
+        let json =
+            format!(r#"{{"times": [{{"t": 0, "enabled_rules": [], "renames": {renames}}}]}}"#);
+        let err = Registry::from_json(&json).unwrap_err().to_string();
+        assert!(err.contains("which names another rule"), "{err}");
+    }
+    let err = Registry::from_json(
+        r#"{"times": [
+            {"t": 0, "enabled_rules": [], "renames": {"ImpR": "R"}},
+            {"t": 1, "enabled_rules": [], "renames": {"AndR": "R"}}
+        ]}"#,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "slice t=1 renames AndR to `R`, which names another rule"
+    );
+    // Renaming a rule to one of its own aliases is fine.
+    Registry::from_json(r#"{"times": [{"t": 0, "enabled_rules": [], "renames": {"Id": "Ax"}}]}"#)
+        .unwrap();
+}
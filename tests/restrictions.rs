@@ -0,0 +1,223 @@
+// tests/restrictions.rs
+use proof_transport::{
+    ast::Proof,
+    cert::RewriteStep,
+    eta::EtaError,
+    registry::{Registry, RuleId},
+    restrict::Restriction,
+    transport::{transport_with_certificate, TransportError},
+    validator::{validate_at, validate_with, ValidationError},
+};
+
+fn registry(restrictions: &str) -> Registry {
+    Registry::from_json(&format!(
+        r#"{{"times": [
+            {{"t": 0, "enabled_rules": ["Id", "Cut", "AndR", "AndL1"]}},
+            {{"t": 1, "enabled_rules": ["Id", "Cut", "AndR", "AndL1"], "restrictions": {restrictions}}}
+        ]}}"#
+    ))
+    .unwrap()
+}
+
+/// `P ⊢ P ∧ P`, with a cut on `P` on the left and a cut on `P ∧ P` on
+/// the right. Rooted at `cc`, it proves `P ⊢ P` with a cut on `P ∧ P` alone.
+fn two_cuts(root: &str) -> Proof {
+    serde_json::from_str(&format!(
+        r#"{{"root": "{root}", "nodes": [
+            {{"id": "both", "rule": "AndR", "premises": ["ac", "cc"], "sequent": {{"ctx": ["P"], "thm": "P ∧ P"}}}},
+            {{"id": "ac", "rule": "Cut", "premises": ["a", "a"], "sequent": {{"ctx": ["P"], "thm": "P"}}}},
+            {{"id": "cc", "rule": "Cut", "premises": ["pair", "proj"], "sequent": {{"ctx": ["P"], "thm": "P"}}}},
+            {{"id": "pair", "rule": "AndR", "premises": ["a", "a"], "sequent": {{"ctx": ["P"], "thm": "P ∧ P"}}}},
+            {{"id": "proj", "rule": "AndL1", "premises": ["h"], "sequent": {{"ctx": ["P", "P ∧ P"], "thm": "P"}}}},
+            {{"id": "h", "rule": "Id", "premises": [], "sequent": {{"ctx": ["P", "P ∧ P"], "thm": "P"}}}},
+            {{"id": "a", "rule": "Id", "premises": [], "sequent": {{"ctx": ["P"], "thm": "P"}}}}
+        ]}}"#
+    ))
+    .unwrap()
+}
+
+fn cuts(p: &Proof) -> Vec<&str> {
+    p.nodes
+        .iter()
+        .filter(|n| n.rule == "Cut")
+        .map(|n| n.id.as_str())
+        .collect()
+}
+
+#[test]
+fn validation_checks_restrictions() {
+    let reg = registry(r#"{"Cut": ["atomic"]}"#);
+    let p = two_cuts("both");
+    validate_with(&p, &reg).expect("restrictions are a matter of time");
+    validate_at(&p, &reg, 0).expect("no restriction at t=0");
+    let err = validate_at(&p, &reg, 1).unwrap_err();
+    assert_eq!(
+        err,
+        ValidationError::Restricted {
+            node: "cc".into(),
+            rule: "Cut".into(),
+            restriction: Restriction::Atomic,
+            t: 1,
+        }
+    );
+    assert_eq!(err.code(), "rule-restricted");
+    assert_eq!(
+        err.to_string(),
+        "node cc breaks the atomic restriction on Cut at t=1"
+    );
+
+    // `P ∧ P` is part of `P ⊢ P ∧ P`, but not of `P ⊢ P`.
+    let reg = registry(r#"{"Cut": ["analytic"]}"#);
+    validate_at(&two_cuts("both"), &reg, 1).expect("analytic cuts");
+    assert!(matches!(
+        validate_at(&two_cuts("cc"), &reg, 1),
+        Err(ValidationError::Restricted { ref node, restriction: Restriction::Analytic, .. }) if node == "cc"
+    ));
+}
+
+#[test]
+fn transport_eliminates_only_the_offending_cuts() {
+    let reg = registry(r#"{"Cut": ["atomic"]}"#);
+    let p = two_cuts("both");
+    let (out, cert) = transport_with_certificate(&p, &reg, 0, 1).expect("transport");
+    assert_eq!(cuts(&out), ["ac"]);
+    assert_eq!(
+        cert.steps,
+        [RewriteStep::RestrictedCutElimination { removed: 1 }]
+    );
+    validate_at(&out, &reg, 1).expect("valid at t=1");
+    cert.verify(&p, &out, Some(&reg))
+        .expect("certificate verifies");
+
+    // Transport back needs no rewrites.
+    let (_, cert) = transport_with_certificate(&out, &reg, 1, 0).expect("transport back");
+    assert!(cert.steps.is_empty());
+
+    let reg = registry(r#"{"Cut": ["analytic"]}"#);
+    let (out, _) = transport_with_certificate(&two_cuts("cc"), &reg, 0, 1).expect("transport");
+    assert!(cuts(&out).is_empty());
+    validate_at(&out, &reg, 1).expect("valid at t=1");
+}
+
+#[test]
+fn transport_fails_when_a_repair_needs_a_disabled_rule() {
+    // Eta-expanding `P ∧ Q ⊢ P ∧ Q` needs AndL2, which is not enabled.
+    let reg = registry(r#"{"Id": ["atomic"]}"#);
+    let p: Proof = serde_json::from_str(
+        r#"{"root": "ax", "nodes": [
+            {"id": "ax", "rule": "Id", "premises": [], "sequent": {"ctx": ["P ∧ Q"], "thm": "P ∧ Q"}}
+        ]}"#,
+    )
+    .unwrap();
+    let err = transport_with_certificate(&p, &reg, 0, 1).unwrap_err();
+    assert_eq!(
+        err,
+        TransportError::EtaExpansion(EtaError::RuleDisabled {
+            node: "ax".into(),
+            rule: RuleId::AndL2,
+        })
+    );
+    let TransportError::EtaExpansion(source) = err else {
+        unreachable!()
+    };
+    assert_eq!(
+        source.to_string(),
+        "cannot eta-expand node ax: it needs AndL2, which is not enabled"
+    );
+}
+
+#[test]
+fn registry_checks_restrictions() {
+    let slice = |restrictions: &str| {
+        Registry::from_json(&format!(
+            r#"{{"times": [{{"t": 0, "enabled_rules": ["Id"], "restrictions": {restrictions}}}]}}"#
+        ))
+    };
+    let reg = slice(r#"{"Id": ["atomic"]}"#).unwrap();
+    assert_eq!(reg.restrictions_at(RuleId::Id, 5), [Restriction::Atomic]);
+    assert!(reg.restrictions_at(RuleId::Cut, 5).is_empty());
+
+    let err = slice(r#"{"Cut": ["atomic"]}"#).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "slice t=0 restricts Cut, which it does not enable"
+    );
+    let err = slice(r#"{"Id": ["analytic"]}"#).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "slice t=0: Id cannot have the analytic restriction"
+    );
+}
+
+#[test]
+fn transport_keeps_allowed_cuts_above_offending_ones() {
+    // The cut on `P ∧ P` breaks the restriction; the atomic cut `ac` in
+    // its right premise does not, and survives its reduction.
+    let reg = registry(r#"{"Cut": ["atomic"]}"#);
+    let p: Proof = serde_json::from_str(
+        r#"{"root": "cc", "nodes": [
+            {"id": "cc", "rule": "Cut", "premises": ["pair", "both"], "sequent": {"ctx": ["P"], "thm": "P ∧ P"}},
+            {"id": "pair", "rule": "AndR", "premises": ["a", "a"], "sequent": {"ctx": ["P"], "thm": "P ∧ P"}},
+            {"id": "both", "rule": "AndR", "premises": ["proj", "ac"], "sequent": {"ctx": ["P", "P ∧ P"], "thm": "P ∧ P"}},
+            {"id": "proj", "rule": "AndL1", "premises": ["h"], "sequent": {"ctx": ["P", "P ∧ P"], "thm": "P"}},
+            {"id": "h", "rule": "Id", "premises": [], "sequent": {"ctx": ["P", "P ∧ P"], "thm": "P"}},
+            {"id": "ac", "rule": "Cut", "premises": ["a", "a"], "sequent": {"ctx": ["P"], "thm": "P"}},
+            {"id": "a", "rule": "Id", "premises": [], "sequent": {"ctx": ["P"], "thm": "P"}}
+        ]}"#,
+    )
+    .unwrap();
+    let (out, cert) = transport_with_certificate(&p, &reg, 0, 1).expect("transport");
+    assert_eq!(cuts(&out), ["ac"]);
+    assert_eq!(
+        cert.steps,
+        [RewriteStep::RestrictedCutElimination { removed: 1 }]
+    );
+    validate_at(&out, &reg, 1).expect("valid at t=1");
+    cert.verify(&p, &out, Some(&reg))
+        .expect("certificate verifies");
+}
+
+#[test]
+fn transport_reduces_complex_cuts_to_atomic_ones() {
+    // Reducing the cut on `Q ∧ Q` leaves a cut on `Q`, which may stay.
+    let reg = Registry::from_json(
+        r#"{"times": [
+            {"t": 0, "enabled_rules": ["Id", "Cut", "AndR", "AndL1", "ImpL", "Or1"]},
+            {"t": 1, "enabled_rules": ["Id", "Cut", "AndR", "AndL1", "ImpL", "Or1"],
+             "restrictions": {"Cut": ["atomic"]}}
+        ]}"#,
+    )
+    .unwrap();
+    let p: Proof = serde_json::from_str(
+        r#"{"root": "cc", "nodes": [
+            {"id": "cc", "rule": "Cut", "premises": ["pair", "proj"], "sequent": {"ctx": ["A ⇒ Q", "A"], "thm": "Q ∨ Q"}},
+            {"id": "pair", "rule": "AndR", "premises": ["q", "q"], "sequent": {"ctx": ["A ⇒ Q", "A"], "thm": "Q ∧ Q"}},
+            {"id": "q", "rule": "ImpL", "premises": ["ha", "hq"], "sequent": {"ctx": ["A ⇒ Q", "A"], "thm": "Q"}},
+            {"id": "ha", "rule": "Id", "premises": [], "sequent": {"ctx": ["A ⇒ Q", "A"], "thm": "A"}},
+            {"id": "hq", "rule": "Id", "premises": [], "sequent": {"ctx": ["A ⇒ Q", "A", "Q"], "thm": "Q"}},
+            {"id": "proj", "rule": "AndL1", "premises": ["o"], "sequent": {"ctx": ["A ⇒ Q", "A", "Q ∧ Q"], "thm": "Q ∨ Q"}},
+            {"id": "o", "rule": "Or1", "premises": ["h"], "sequent": {"ctx": ["A ⇒ Q", "A", "Q ∧ Q", "Q"], "thm": "Q ∨ Q"}},
+            {"id": "h", "rule": "Id", "premises": [], "sequent": {"ctx": ["A ⇒ Q", "A", "Q ∧ Q", "Q"], "thm": "Q"}}
+        ]}"#,
+    )
+    .unwrap();
+    validate_at(&p, &reg, 0).expect("valid at t=0");
+    let (out, cert) = transport_with_certificate(&p, &reg, 0, 1).expect("transport");
+    let cut = out
+        .nodes
+        .iter()
+        .find(|n| n.rule == "Cut")
+        .expect("a cut remains");
+    assert_eq!(cuts(&out).len(), 1);
+    assert_eq!(
+        out.node(&cut.premises[0]).unwrap().sequent.succ[0].to_string(),
+        "Q"
+    );
+    assert_eq!(
+        cert.steps,
+        [RewriteStep::RestrictedCutElimination { removed: 1 }]
+    );
+    validate_at(&out, &reg, 1).expect("valid at t=1");
+    cert.verify(&p, &out, Some(&reg))
+        .expect("certificate verifies");
+}
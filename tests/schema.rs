@@ -0,0 +1,138 @@
+// tests/schema.rs
+use proof_transport::{
+    ast::Proof,
+    registry::Registry,
+    schema::{RuleSchema, SchemaError, SideCondition},
+    validator::{validate_all_with, validate_local_wf, validate_with, ValidationError},
+};
+
+mod support;
+use support::load;
+
+fn kernel() -> Registry {
+    Registry::from_path("examples/kernel/R.json").expect("registry")
+}
+
+fn with_node(p: &Proof, id: &str, rule: &str, sequent: &str) -> Proof {
+    let mut p = p.clone();
+    let n = p.nodes.iter_mut().find(|n| n.id == id).unwrap();
+    n.rule = rule.to_string();
+    n.sequent = serde_json::from_str(sequent).unwrap();
+    p
+}
+
+#[test]
+fn schema_rules_validate_against_their_registry() {
+    let p = load("examples/kernel/and_swap.json");
+    let reg = kernel();
+    validate_with(&p, &reg).expect("valid with the kernel's schemas");
+    assert!(validate_all_with(&p, &reg).is_empty());
+
+    // Without the registry the rule names are unknown.
+    assert!(matches!(
+        validate_local_wf(&p),
+        Err(ValidationError::UnknownRule { rule, .. }) if rule == "AndE2"
+    ));
+    assert_eq!(reg.schemas_at(1).into_iter().collect::<Vec<_>>(), ["BotE"]);
+}
+
+#[test]
+fn schema_instances_are_checked() {
+    let p = load("examples/kernel/and_swap.json");
+    let reg = kernel();
+
+    // `A` is not the right conjunct of `A ∧ B`.
+    let wrong = with_node(&p, "b", "AndE2", r#"{"ctx": ["A ∧ B"], "thm": "A"}"#);
+    let diags = validate_all_with(&wrong, &reg);
+    let at_b = diags
+        .iter()
+        .find(|d| d.node.as_deref() == Some("b"))
+        .unwrap();
+    assert_eq!(at_b.code, "rule-check");
+    assert!(
+        at_b.message.contains("fails rule AndE2"),
+        "{}",
+        at_b.message
+    );
+
+    // `BotE` only concludes atoms, and its premise must prove ⊥.
+    let atom = with_node(&p, "a", "BotE", r#"{"ctx": ["A ∧ B"], "thm": "A"}"#);
+    let err = validate_with(&atom, &reg).unwrap_err();
+    assert!(
+        matches!(&err, ValidationError::SchemaCheck { node, rule, .. } if node == "a" && rule == "BotE"),
+        "{err}"
+    );
+    assert!(
+        err.to_string()
+            .contains("premise 1 has no formula matching ⊥"),
+        "{err}"
+    );
+
+    let mut bot = p.clone();
+    bot.root = "e".into();
+    bot.nodes = serde_json::from_str(
+        r#"[
+        { "id": "e", "rule": "BotE", "premises": ["h"], "sequent": { "ctx": ["⊥"], "thm": "A ∧ B" } },
+        { "id": "h", "rule": "Id", "premises": [], "sequent": { "ctx": ["⊥"], "thm": "⊥" } }
+        ]"#,
+    )
+    .unwrap();
+    let err = validate_with(&bot, &reg).unwrap_err().to_string();
+    assert!(err.contains("A must be atomic, found A ∧ B"), "{err}");
+}
+
+#[test]
+fn registry_checks_its_schemas() {
+    let builtin = r#"{"rules": [{"name": "Axiom", "conclusion": "A"}], "times": []}"#;
+    let err = Registry::from_json(builtin).unwrap_err().to_string();
+    assert!(err.contains("has the name of a built-in rule"), "{err}");
+
+    let undeclared = r#"{"times": [{"t": 0, "enabled_rules": [], "enabled_schemas": ["AndE1"]}]}"#;
+    let err = Registry::from_json(undeclared).unwrap_err().to_string();
+    assert!(
+        err.contains("enables rule schema `AndE1`, which is not declared"),
+        "{err}"
+    );
+
+    let twice = r#"{"rules": [{"name": "R", "conclusion": "A"}, {"name": "R", "conclusion": "B"}],
+                    "times": []}"#;
+    let err = Registry::from_json(twice).unwrap_err().to_string();
+    assert!(err.contains("declared twice"), "{err}");
+}
+
+#[test]
+fn schema_errors_name_the_schema() {
+    let schema = |name: &str, conclusion: &str, meta: &str| RuleSchema {
+        name: name.into(),
+        metavariables: vec!["A".into()],
+        premises: Vec::new(),
+        conclusion: serde_json::from_value(serde_json::json!({"ctx": [], "thm": conclusion}))
+            .unwrap(),
+        side_conditions: vec![SideCondition::Atomic { meta: meta.into() }],
+    };
+    assert_eq!(
+        schema("Ax", "A", "A").check_well_formed(),
+        Err(SchemaError::BuiltinName {
+            schema: "Ax".into()
+        })
+    );
+    assert!(matches!(
+        schema("R", "A ∧", "A").check_well_formed(),
+        Err(SchemaError::Parse { ref schema, .. }) if schema == "R"
+    ));
+    let err = schema("R", "A", "B").check_well_formed().unwrap_err();
+    assert_eq!(
+        err,
+        SchemaError::UnknownMetavariable {
+            schema: "R".into(),
+            meta: "B".into()
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "rule schema `R`: side condition on `B`, which is not a metavariable"
+    );
+    schema("R", "A", "A")
+        .check_well_formed()
+        .expect("well-formed");
+}
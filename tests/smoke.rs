@@ -12,7 +12,7 @@ fn load_proof(path: &str) -> Proof {
 
 #[test]
 fn loads_and_scores_example() {
-    let p = load_proof("examples/proof_with_cut.json");
+    let p = load_proof("examples/proof_with_cut_valid.json");
     validate_local_wf(&p).expect("well-formed proof");
     assert!(
         fragility_score(&p) > 1,
@@ -22,7 +22,7 @@ fn loads_and_scores_example() {
 
 #[test]
 fn cut_elimination_root_and_all_compile() {
-    let p = load_proof("examples/proof_with_cut.json");
+    let p = load_proof("examples/proof_with_cut_valid.json");
     let _ = cut_eliminate_root(&p);
     let _ = cut_eliminate_all(&p);
 }
@@ -0,0 +1,187 @@
+// tests/structural.rs
+use proof_transport::{
+    ast::{Contexts, Formula, Proof},
+    cert::RewriteStep,
+    registry::{Registry, RuleId},
+    transport::{transport_with_certificate, TransportError},
+    validator::validate_local_wf,
+};
+
+mod support;
+use support::load;
+
+fn with(contexts: Contexts) -> Proof {
+    Proof {
+        contexts,
+        ..load("examples/structural_list.json")
+    }
+}
+
+fn reason(p: &Proof) -> String {
+    validate_local_wf(p).unwrap_err().to_string()
+}
+
+#[test]
+fn structural_rules_follow_the_context_discipline() {
+    for contexts in [Contexts::Set, Contexts::Multiset, Contexts::List] {
+        validate_local_wf(&with(contexts)).unwrap_or_else(|e| panic!("{contexts:?}: {e}"));
+    }
+
+    // Weakening by two formulas at once is only absorbed by sets.
+    let mut p = with(Contexts::Multiset);
+    p.root = "weak".into();
+    p.nodes.truncate(2);
+    p.nodes[1].sequent.ctx.insert(0, Formula::Text("C".into()));
+    assert!(
+        reason(&p).contains("conclusion must add exactly one formula on the left"),
+        "{}",
+        reason(&p)
+    );
+    p.contexts = Contexts::Set;
+    validate_local_wf(&p).expect("sets absorb weakening");
+
+    // Lists weaken at the left end and exchange neighbours only.
+    let mut p = with(Contexts::List);
+    p.nodes[1].sequent.ctx.reverse();
+    assert!(reason(&p).contains("must add one formula at the left end"));
+    let mut p = with(Contexts::List);
+    p.nodes[2].sequent.ctx = p.nodes[1].sequent.ctx.clone();
+    assert!(reason(&p).contains("must swap two neighbouring formulas"));
+    p.contexts = Contexts::Multiset;
+    validate_local_wf(&p).expect("multisets ignore order");
+
+    // Contraction must drop exactly one copy.
+    let mut p = with(Contexts::Multiset);
+    p.nodes[4].sequent.ctx.remove(0);
+    assert!(reason(&p).contains("premise must repeat exactly one formula"));
+}
+
+#[test]
+fn right_structural_rules_need_the_classical_calculus() {
+    let p: Proof = serde_json::from_value(serde_json::json!({
+        "root": "w",
+        "nodes": [
+            { "id": "ax", "rule": "Id", "sequent": { "ctx": ["A"], "thm": "A" } },
+            { "id": "w", "rule": "WeakR", "premises": ["ax"], "sequent": { "ctx": ["A"], "thm": "A" } }
+        ]
+    }))
+    .unwrap();
+    assert!(reason(&p).contains("WeakR needs the classical calculus"));
+}
+
+#[test]
+fn transport_drops_disabled_structural_rules() {
+    let reg = Registry::from_json(
+        r#"{"times": [
+            {"t": 0, "enabled_rules": ["Id", "ImpR", "OrR", "WeakL", "WeakR", "ContrL", "ExchL"]},
+            {"t": 1, "enabled_rules": ["Id", "ImpR", "OrR"]}
+        ]}"#,
+    )
+    .unwrap();
+
+    for (p, removed) in [
+        (with(Contexts::Set), 4),
+        (load("examples/classical_excluded_middle.json"), 1),
+    ] {
+        let (q, cert) = transport_with_certificate(&p, &reg, 0, 1).expect("transport");
+        validate_local_wf(&q).expect("output is well-formed");
+        assert!(
+            q.nodes
+                .iter()
+                .all(|n| !RuleId::from_name(&n.rule).unwrap().is_structural()),
+            "{:?}",
+            q.nodes
+        );
+        assert_eq!(
+            cert.steps,
+            vec![RewriteStep::StructuralElimination { removed }]
+        );
+        cert.verify(&p, &q, Some(&reg))
+            .expect("certificate verifies");
+    }
+}
+
+#[test]
+fn structural_rules_are_not_admissible_in_counted_contexts() {
+    let reg = Registry::from_json(
+        r#"{"times": [
+            {"t": 0, "enabled_rules": ["Id", "WeakL", "ContrL", "ExchL"]},
+            {"t": 1, "enabled_rules": ["Id"]}
+        ]}"#,
+    )
+    .unwrap();
+    for contexts in [Contexts::Multiset, Contexts::List] {
+        let err = transport_with_certificate(&with(contexts), &reg, 0, 1).unwrap_err();
+        assert!(
+            matches!(&err, TransportError::NoStrategy { t: 1, uses } if uses.len() == 4),
+            "{contexts:?}: {err:?}"
+        );
+    }
+}
+
+#[test]
+fn logical_rules_need_exact_contexts_when_they_count() {
+    let and_r = |contexts: &str, left: &[&str], right: &[&str]| -> Proof {
+        serde_json::from_value(serde_json::json!({
+            "contexts": contexts,
+            "root": "and",
+            "nodes": [
+                { "id": "a", "rule": "Id", "sequent": { "ctx": left, "thm": "A" } },
+                { "id": "b", "rule": "Id", "sequent": { "ctx": right, "thm": "B" } },
+                { "id": "and", "rule": "AndR", "premises": ["a", "b"],
+                  "sequent": { "ctx": ["C", "A", "B"], "thm": "A ∧ B" } }
+            ]
+        }))
+        .unwrap()
+    };
+
+    // Sets let premises drop and reorder hypotheses.
+    validate_local_wf(&and_r("set", &["A"], &["B", "A"])).expect("sets absorb weakening");
+    for contexts in ["multiset", "list"] {
+        let p = and_r(contexts, &["A"], &["B", "A"]);
+        assert!(
+            reason(&p).contains("premise 1 must have exactly the hypotheses C, A, B"),
+            "{contexts}: {}",
+            reason(&p)
+        );
+    }
+
+    // Multisets ignore order; lists do not.
+    let p = and_r("multiset", &["C", "A", "B"], &["B", "C", "A"]);
+    validate_local_wf(&p).expect("multisets ignore order");
+    let p = and_r("list", &["C", "A", "B"], &["B", "C", "A"]);
+    assert!(
+        reason(&p).contains("premise 2 must have exactly the hypotheses C, A, B, in this order"),
+        "{}",
+        reason(&p)
+    );
+    validate_local_wf(&and_r("list", &["C", "A", "B"], &["C", "A", "B"])).expect("exact");
+
+    // Left rules consume their principal formula, which under lists stands
+    // at the left end.
+    let and_l = |contexts: &str, concl: &[&str], prem: &[&str]| -> Proof {
+        serde_json::from_value(serde_json::json!({
+            "contexts": contexts,
+            "root": "l",
+            "nodes": [
+                { "id": "ax", "rule": "Id", "sequent": { "ctx": prem, "thm": "A" } },
+                { "id": "l", "rule": "AndL1", "premises": ["ax"],
+                  "sequent": { "ctx": concl, "thm": "A" } }
+            ]
+        }))
+        .unwrap()
+    };
+    validate_local_wf(&and_l("list", &["A ∧ B", "C"], &["A", "C"])).expect("exact");
+    let p = and_l("multiset", &["A ∧ B", "C"], &["A ∧ B", "A", "C"]);
+    assert!(
+        reason(&p).contains("exactly the hypotheses A, C"),
+        "{}",
+        reason(&p)
+    );
+    let p = and_l("list", &["C", "A ∧ B"], &["C", "A"]);
+    assert!(
+        reason(&p).contains("principal formula A ∧ B is not at the left end"),
+        "{}",
+        reason(&p)
+    );
+}
@@ -13,7 +13,7 @@ fn load_proof(path: &str) -> Proof {
 
 #[test]
 fn lib_exports_compile() {
-    let p = load_proof("examples/proof_with_cut.json");
+    let p = load_proof("examples/proof_with_cut_valid.json");
     validate_local_wf(&p).expect("well-formed proof");
     let score = fragility_score(&p);
     assert!(score >= 1, "fragility score should be at least 1");
@@ -6,10 +6,10 @@ use proof_transport::ast::Proof;
 /// Parse a proof file.
 ///
 /// Strategy:
-///   1) try strict JSON (serde_json) from a file reader
-///   2) if that fails, read the file to a string and try:
-///        a) strict `serde_json::from_str` (handles some edge cases)
-///        b) permissive `json5::from_str` (allows comments/trailing commas)
+/// 1) try strict JSON (serde_json) from a file reader
+/// 2) if that fails, read the file to a string and try:
+///    a) strict `serde_json::from_str` (handles some edge cases)
+///    b) permissive `json5::from_str` (allows comments/trailing commas)
 pub fn parse_proof<P: AsRef<Path>>(path: P) -> anyhow::Result<Proof> {
     let path = path.as_ref();
 
@@ -3,7 +3,13 @@ use serde_json::from_reader;
 use std::fs::File;
 
 use proof_transport::{
-    ast::Proof, cutelim::cut_eliminate_all, frag::fragility_score, validator::validate_local_wf,
+    ast::{Calculus, Contexts, Proof},
+    cutelim::cut_eliminate_all,
+    frag::fragility_score,
+    registry::Registry,
+    transport::{strategy, transport, RuleUse, Strategy, TransportError},
+    unfold::Connective,
+    validator::validate_local_wf,
 };
 
 fn load(path: &str) -> Proof {
@@ -15,9 +21,9 @@ fn load(path: &str) -> Proof {
 #[test]
 fn fragility_strictly_drops_on_cut_examples() {
     let paths = [
-        "examples/proof_with_cut.json",  // existing root Cut
-        "examples/proof_cut_chain.json", // nested/internal Cut
-        "examples/proof_cut_pair.json",  // sibling Cuts
+        "examples/proof_with_cut_valid.json",  // existing root Cut
+        "examples/proof_cut_chain_valid.json", // nested/internal Cut
+        "examples/proof_cut_pair_valid.json",  // sibling Cuts
     ];
 
     for path in paths {
@@ -25,7 +31,7 @@ fn fragility_strictly_drops_on_cut_examples() {
         validate_local_wf(&p).expect("wf before");
 
         let before = fragility_score(&p);
-        let q = cut_eliminate_all(&p);
+        let q = cut_eliminate_all(&p).unwrap();
         validate_local_wf(&q).expect("wf after");
 
         let after = fragility_score(&q);
@@ -41,11 +47,11 @@ fn fragility_strictly_drops_on_cut_examples() {
 #[test]
 fn fragility_never_increases_on_all_examples() {
     let paths = [
-        "examples/proof_with_cut.json",
-        "examples/proof_cut_chain.json",
-        "examples/proof_cut_pair.json",
-        "examples/proof_fo_quantifiers.json",
-        "examples/proof_with_unreachable.json",
+        "examples/proof_with_cut_valid.json",
+        "examples/proof_cut_chain_valid.json",
+        "examples/proof_cut_pair_valid.json",
+        "examples/proof_fo_quantifiers_valid.json",
+        "examples/proof_with_unreachable_valid.json",
         "examples/proof_cut_free.json",
     ];
 
@@ -54,7 +60,7 @@ fn fragility_never_increases_on_all_examples() {
         validate_local_wf(&p).expect("wf before");
 
         let before = fragility_score(&p);
-        let q = cut_eliminate_all(&p);
+        let q = cut_eliminate_all(&p).unwrap();
         validate_local_wf(&q).expect("wf after");
 
         let after = fragility_score(&q);
@@ -69,11 +75,11 @@ fn fragility_never_increases_on_all_examples() {
 /// `ghost` example we added to exercise graph cleanup.
 #[test]
 fn unreachable_nodes_are_pruned() {
-    let p = load("examples/proof_with_unreachable.json");
+    let p = load("examples/proof_with_unreachable_valid.json");
     validate_local_wf(&p).expect("wf before");
 
     let before_nodes = p.nodes.len();
-    let q = cut_eliminate_all(&p); // elimination + subsequent prune()
+    let q = cut_eliminate_all(&p).unwrap(); // elimination + subsequent prune()
     validate_local_wf(&q).expect("wf after");
     let after_nodes = q.nodes.len();
 
@@ -82,3 +88,105 @@ fn unreachable_nodes_are_pruned() {
         "expected prune to drop unreachable nodes: {before_nodes} -> {after_nodes}"
     );
 }
+
+/// Transport never changes what a proof proves.
+#[test]
+fn transport_keeps_the_end_sequent() {
+    let reg = proof_transport::Registry::from_path("examples/R.json").expect("registry");
+    for path in [
+        "examples/proof_with_cut_valid.json",
+        "examples/proof_cut_chain_valid.json",
+        "examples/proof_cut_pair_valid.json",
+        "examples/or_proof_with_cut_valid.json",
+    ] {
+        let p = load(path);
+        let q = proof_transport::transport(&p, &reg, 0, 1).expect("transport");
+        let before = proof_transport::resolve_proof(&p).unwrap();
+        assert!(
+            before
+                .root_node()
+                .unwrap()
+                .sequent
+                .alpha_eq(&q.root_node().unwrap().sequent),
+            "{path}: end sequent changed"
+        );
+    }
+}
+
+/// Every disabled rule needs a strategy; the error names each inference
+/// that has none, not just the first.
+#[test]
+fn transport_reports_every_use_of_a_rule_without_strategy() {
+    let reg = Registry::from_json(
+        r#"{"times": [{"t": 0, "enabled_rules": ["Id", "Cut", "NotL"]}, {"t": 1, "enabled_rules": []}]}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        strategy(&reg, "Cut", 1, Calculus::Intuitionistic, Contexts::Set),
+        Some(Strategy::CutElimination)
+    );
+    assert_eq!(
+        strategy(&reg, "ContrL", 1, Calculus::Intuitionistic, Contexts::Set),
+        Some(Strategy::StructuralElimination)
+    );
+    assert_eq!(
+        strategy(&reg, "¬L", 1, Calculus::Intuitionistic, Contexts::Set),
+        Some(Strategy::Unfold(Connective::Not))
+    );
+    assert_eq!(
+        strategy(&reg, "Axiom", 1, Calculus::Intuitionistic, Contexts::Set),
+        None
+    );
+
+    let p = load("examples/proof_with_cut_valid.json");
+    let err = transport(&p, &reg, 0, 1).unwrap_err();
+    let uses = ["n1", "n2"].map(|node| RuleUse {
+        node: node.into(),
+        rule: "Id".into(),
+    });
+    assert_eq!(
+        err,
+        TransportError::NoStrategy {
+            t: 1,
+            uses: uses.to_vec()
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "no strategy removes the rules disabled at t=1: n1 (Id), n2 (Id)"
+    );
+}
+
+/// Cut elimination only reduces intuitionistic proofs with set contexts,
+/// so elsewhere a disabled `Cut` has no strategy.
+#[test]
+fn cut_elimination_is_no_strategy_for_classical_or_counted_contexts() {
+    let reg = Registry::from_json(
+        r#"{"times": [{"t": 0, "enabled_rules": ["Id", "Cut"]}, {"t": 1, "enabled_rules": ["Id"]}]}"#,
+    )
+    .unwrap();
+    for (calculus, contexts) in [
+        (Calculus::Classical, Contexts::Set),
+        (Calculus::Intuitionistic, Contexts::Multiset),
+    ] {
+        assert_eq!(strategy(&reg, "Cut", 1, calculus, contexts), None);
+    }
+
+    let mut p = load("examples/proof_with_cut_valid.json");
+    p.calculus = Calculus::Classical;
+    let err = transport(&p, &reg, 0, 1).unwrap_err();
+    let TransportError::NoStrategy { t: 1, uses } = err else {
+        panic!("expected NoStrategy, got {err:?}");
+    };
+    let cuts: Vec<&str> = p
+        .nodes
+        .iter()
+        .filter(|n| n.rule == "Cut")
+        .map(|n| n.id.as_str())
+        .collect();
+    assert!(!cuts.is_empty());
+    assert_eq!(
+        uses.iter().map(|u| u.node.as_str()).collect::<Vec<_>>(),
+        cuts
+    );
+}
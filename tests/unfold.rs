@@ -0,0 +1,143 @@
+// tests/unfold.rs
+use proof_transport::{
+    ast::Proof,
+    cert::RewriteStep,
+    parse::resolve_proof,
+    registry::{Registry, RuleId},
+    transport::{transport_with_certificate, TransportError},
+    unfold::{unfold_sequent, Connective},
+    validator::{validate_local_wf, ValidationError},
+};
+
+mod support;
+use support::load;
+
+/// Everything is enabled at t=0; `¬` and `⇔` lose their rules at t=1.
+fn registry() -> Registry {
+    Registry::from_json(
+        r#"{"times": [
+            {"t": 0, "enabled_rules": ["Id", "BotI", "AndL1", "AndL2", "AndR", "ImpL", "ImpR",
+                                       "NotL", "NotR", "IffL", "IffR"]},
+            {"t": 1, "enabled_rules": ["Id", "BotI", "AndL1", "AndL2", "AndR", "ImpL", "ImpR"]}
+        ]}"#,
+    )
+    .expect("registry")
+}
+
+/// `⊢ A ⇔ A`, by `IffR` over two axioms.
+fn iff_refl() -> Proof {
+    serde_json::from_str(
+        r#"{"root": "iff", "nodes": [
+            {"id": "ax", "rule": "Id", "premises": [], "sequent": {"ctx": ["A"], "thm": "A"}},
+            {"id": "iff", "rule": "IffR", "premises": ["ax", "ax"], "sequent": {"ctx": [], "thm": "A ⇔ A"}}
+        ]}"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn negation_and_biconditional_rules_check() {
+    validate_local_wf(&load("examples/proof_not_iff.json")).expect("well-formed");
+    validate_local_wf(&iff_refl()).expect("well-formed");
+
+    // IffL adds both implications, not the bare formulas.
+    let mut p = load("examples/proof_not_iff.json");
+    let node = p.nodes.iter_mut().find(|n| n.id == "iff").unwrap();
+    node.sequent.succ = vec![proof_transport::Formula::Text("A".into())];
+    match validate_local_wf(&p) {
+        Err(ValidationError::RuleCheck { node, rule, .. }) => {
+            assert_eq!(node, "iff");
+            assert_eq!(rule, RuleId::IffL);
+        }
+        other => panic!("expected a rule check failure, got {other:?}"),
+    }
+}
+
+#[test]
+fn transport_unfolds_connectives_whose_rules_are_disabled() {
+    let reg = registry();
+    for (p, connectives) in [
+        (
+            load("examples/proof_not_iff.json"),
+            vec![Connective::Not, Connective::Iff],
+        ),
+        (iff_refl(), vec![Connective::Iff]),
+    ] {
+        let (q, cert) = transport_with_certificate(&p, &reg, 0, 1).expect("transport");
+        validate_local_wf(&q).expect("output is well-formed");
+        assert!(q.nodes.iter().all(|n| reg
+            .enabled_at(1)
+            .contains(&RuleId::from_name(&n.rule).unwrap())));
+
+        let unfolded: Vec<Connective> = cert
+            .steps
+            .iter()
+            .map(|s| match s {
+                RewriteStep::Unfold { connective, .. } => *connective,
+                other => panic!("unexpected step {other:?}"),
+            })
+            .collect();
+        assert_eq!(unfolded, connectives);
+
+        // The end sequent is the input's, with the connectives unfolded.
+        let before = resolve_proof(&p)
+            .unwrap()
+            .root_node()
+            .unwrap()
+            .sequent
+            .clone();
+        let expected = connectives
+            .iter()
+            .fold(before, |s, c| unfold_sequent(&s, *c));
+        assert_eq!(q.root_node().unwrap().sequent, expected);
+        cert.verify(&p, &q, Some(&reg))
+            .expect("certificate verifies");
+    }
+}
+
+#[test]
+fn nothing_to_unfold_when_the_rules_stay() {
+    let reg = registry();
+    let p = load("examples/proof_not_iff.json");
+    let (q, cert) = transport_with_certificate(&p, &reg, 0, 0).expect("transport");
+    assert_eq!(q, p);
+    assert!(cert.steps.is_empty());
+
+    // Disabling only `NotR` still unfolds every use of `¬`.
+    let reg = Registry::from_json(
+        r#"{"times": [{"t": 0, "enabled_rules": ["Id", "ImpL", "ImpR", "BotI", "NotL", "IffL", "IffR"]}]}"#,
+    )
+    .unwrap();
+    let (q, _) = transport_with_certificate(&p, &reg, 0, 0).expect("transport");
+    assert!(q.nodes.iter().all(|n| n.rule != "NotL" && n.rule != "NotR"));
+    assert!(q.nodes.iter().any(|n| n.rule == "IffL"));
+
+    // A slice without `ImpL` cannot take the proof's own `ImpL`, which no
+    // strategy removes.
+    let reg = Registry::from_json(
+        r#"{"times": [{"t": 0, "enabled_rules": ["Id", "ImpR", "BotI", "IffL"]}]}"#,
+    )
+    .unwrap();
+    assert!(matches!(
+        transport_with_certificate(&p, &reg, 0, 0),
+        Err(TransportError::NoStrategy { uses, .. }) if uses.len() == 1 && uses[0].node == "mp"
+    ));
+
+    // Nor the `ImpL` that unfolding `NotL` introduces.
+    let ax: Proof = serde_json::from_str(
+        r#"{"root": "elim", "nodes": [
+            { "id": "ax", "rule": "Id", "premises": [], "sequent": { "ctx": ["A"], "thm": "A" } },
+            { "id": "elim", "rule": "NotL", "premises": ["ax"], "sequent": { "ctx": ["¬A", "A"], "thm": "⊥" } }
+        ]}"#,
+    )
+    .unwrap();
+    let reg =
+        Registry::from_json(r#"{"times": [{"t": 0, "enabled_rules": ["Id", "BotI"]}]}"#).unwrap();
+    assert!(matches!(
+        transport_with_certificate(&ax, &reg, 0, 0),
+        Err(TransportError::RuleDisabled {
+            rule: RuleId::ImpL,
+            ..
+        })
+    ));
+}
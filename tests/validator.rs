@@ -0,0 +1,290 @@
+use proof_transport::{
+    ast::{Calculus, Proof},
+    cutelim::{cut_eliminate_all, cut_eliminate_root, cut_eliminate_unless},
+    registry::Registry,
+    transport::{transport, RuleUse, TransportError},
+    validator::{
+        unreachable_warnings, validate_all, validate_all_at, validate_at, validate_local_wf,
+        Severity, ValidationError,
+    },
+};
+use serde_json::json;
+
+fn var(name: &str) -> serde_json::Value {
+    json!({ "tag": "Var", "fields": name })
+}
+
+fn and(a: serde_json::Value, b: serde_json::Value) -> serde_json::Value {
+    json!({ "tag": "And", "fields": [a, b] })
+}
+
+fn proof(v: serde_json::Value) -> Proof {
+    serde_json::from_value(v).expect("decode proof")
+}
+
+#[test]
+fn and_r_and_and_l_check_their_premises() {
+    // A ∧ B ⊢ B ∧ A
+    let p = proof(json!({
+        "root": "r",
+        "nodes": [
+            { "id": "a", "rule": "Id", "premises": [], "sequent": { "ctx": [var("A")], "goal": var("A") } },
+            { "id": "b", "rule": "Id", "premises": [], "sequent": { "ctx": [var("B")], "goal": var("B") } },
+            { "id": "la", "rule": "AndL1", "premises": ["a"], "sequent": { "ctx": [and(var("A"), var("B"))], "goal": var("A") } },
+            { "id": "lb", "rule": "AndL2", "premises": ["b"], "sequent": { "ctx": [and(var("A"), var("B"))], "goal": var("B") } },
+            { "id": "r", "rule": "AndR", "premises": ["lb", "la"], "sequent": { "ctx": [and(var("A"), var("B"))], "goal": and(var("B"), var("A")) } }
+        ]
+    }));
+    validate_local_wf(&p).expect("valid proof");
+
+    // Swapping the premises of AndR breaks it, and the error says where.
+    let mut bad = p.clone();
+    bad.nodes[4].premises.reverse();
+    let err = validate_local_wf(&bad).unwrap_err().to_string();
+    assert!(err.contains("node r"), "{err}");
+    assert!(err.contains("AndR"), "{err}");
+    assert!(err.contains("premise 1"), "{err}");
+}
+
+#[test]
+fn cut_premise_may_not_use_unavailable_hypotheses() {
+    // The right premise of the cut uses `B`, which is neither in the
+    // conclusion's context nor the cut formula.
+    let p = proof(json!({
+        "root": "c",
+        "nodes": [
+            { "id": "a", "rule": "Id", "premises": [], "sequent": { "ctx": [var("A")], "goal": var("A") } },
+            { "id": "b", "rule": "Id", "premises": [], "sequent": { "ctx": [var("B")], "goal": var("B") } },
+            { "id": "c", "rule": "Cut", "premises": ["a", "b"], "sequent": { "ctx": [var("A")], "goal": var("B") } }
+        ]
+    }));
+    let err = validate_local_wf(&p).unwrap_err().to_string();
+    assert!(err.contains("node c fails rule Cut"), "{err}");
+    assert!(err.contains("premise 2 uses hypotheses"), "{err}");
+}
+
+#[test]
+fn axiom_needs_its_formula_in_context() {
+    let p = proof(json!({
+        "root": "n",
+        "nodes": [
+            { "id": "n", "rule": "Id", "premises": [], "sequent": { "ctx": [var("B")], "goal": var("A") } }
+        ]
+    }));
+    let err = validate_local_wf(&p).unwrap_err().to_string();
+    assert!(
+        err.contains("conclusion is not among the hypotheses"),
+        "{err}"
+    );
+}
+
+#[test]
+fn errors_are_structured() {
+    let dangling = proof(json!({
+        "root": "n",
+        "nodes": [
+            { "id": "n", "rule": "AndR", "premises": ["a", "b"], "sequent": { "ctx": [], "goal": var("A") } }
+        ]
+    }));
+    assert_eq!(
+        validate_local_wf(&dangling),
+        Err(ValidationError::DanglingPremise {
+            node: "n".into(),
+            premise: "a".into()
+        })
+    );
+
+    let missing_root = Proof {
+        root: "nope".into(),
+        ..dangling.clone()
+    };
+    assert!(matches!(
+        validate_local_wf(&missing_root),
+        Err(ValidationError::RootMissing { root }) if root == "nope"
+    ));
+
+    // Transport wraps input errors and names what blocks the target time.
+    let reg = Registry::from_json(
+        r#"{"times": [{"t": 0, "enabled_rules": ["Id", "AndR"]}, {"t": 5, "enabled_rules": ["Id"]}]}"#,
+    )
+    .unwrap();
+    assert!(matches!(
+        transport(&dangling, &reg, 0, 5),
+        Err(TransportError::InvalidInput(
+            ValidationError::DanglingPremise { .. }
+        ))
+    ));
+
+    let p = proof(json!({
+        "root": "r",
+        "nodes": [
+            { "id": "a", "rule": "Id", "premises": [], "sequent": { "ctx": [var("A")], "goal": var("A") } },
+            { "id": "r", "rule": "AndR", "premises": ["a", "a"], "sequent": { "ctx": [var("A")], "goal": and(var("A"), var("A")) } }
+        ]
+    }));
+    assert_eq!(
+        transport(&p, &reg, 0, 5),
+        Err(TransportError::NoStrategy {
+            t: 5,
+            uses: vec![RuleUse {
+                node: "r".into(),
+                rule: "AndR".into()
+            }]
+        })
+    );
+}
+
+#[test]
+fn validate_all_reports_every_problem() {
+    let p = proof(json!({
+        "root": "d",
+        "nodes": [
+            { "id": "a", "rule": "Foo", "premises": [], "sequent": { "ctx": [var("A")], "goal": var("A") } },
+            { "id": "b", "rule": "Id", "premises": ["zz"], "sequent": { "ctx": [var("A")], "goal": var("A") } },
+            { "id": "c", "rule": "Id", "premises": [], "sequent": { "ctx": ["A ∧"], "goal": var("A") } },
+            { "id": "d", "rule": "Id", "premises": [], "sequent": { "ctx": [var("B")], "goal": var("A") } }
+        ]
+    }));
+    let diags = validate_all(&p);
+    let found: Vec<(Option<&str>, &str)> =
+        diags.iter().map(|d| (d.node.as_deref(), d.code)).collect();
+    assert_eq!(
+        found,
+        [
+            (Some("a"), "unknown-rule"),
+            (Some("b"), "dangling-premise"),
+            (Some("c"), "parse"),
+            (Some("d"), "rule-check"),
+        ]
+    );
+    assert!(diags.iter().all(|d| d.severity == Severity::Error));
+
+    // The first diagnostic is the error `validate_local_wf` stops at.
+    assert_eq!(
+        validate_local_wf(&p).unwrap_err().to_string(),
+        diags[0].message
+    );
+}
+
+#[test]
+fn graph_checks_catch_duplicates_and_cycles() {
+    let dup = proof(json!({
+        "root": "a",
+        "nodes": [
+            { "id": "a", "rule": "Id", "premises": [], "sequent": { "ctx": [var("A")], "goal": var("A") } },
+            { "id": "a", "rule": "Id", "premises": [], "sequent": { "ctx": [var("B")], "goal": var("B") } }
+        ]
+    }));
+    assert_eq!(
+        validate_local_wf(&dup),
+        Err(ValidationError::DuplicateId { node: "a".into() })
+    );
+
+    // r → n1 → n2 → n1
+    let cyclic = proof(json!({
+        "root": "r",
+        "nodes": [
+            { "id": "r", "rule": "Cut", "premises": ["n1", "a"], "sequent": { "ctx": [var("A")], "goal": var("A") } },
+            { "id": "n1", "rule": "AndL1", "premises": ["n2"], "sequent": { "ctx": [var("A")], "goal": var("A") } },
+            { "id": "n2", "rule": "AndL1", "premises": ["n1"], "sequent": { "ctx": [var("A")], "goal": var("A") } },
+            { "id": "a", "rule": "Id", "premises": [], "sequent": { "ctx": [var("A")], "goal": var("A") } }
+        ]
+    }));
+    let cycle = ValidationError::Cycle {
+        path: vec!["n1".into(), "n2".into(), "n1".into()],
+    };
+    assert_eq!(validate_local_wf(&cyclic), Err(cycle.clone()));
+    assert_eq!(cycle.to_string(), "premise cycle: n1 → n2 → n1");
+    assert_eq!(cut_eliminate_all(&cyclic), Err(cycle.clone()));
+    assert_eq!(cut_eliminate_root(&cyclic), Err(cycle.clone()));
+    assert_eq!(cut_eliminate_unless(&cyclic, &|_| true), Err(cycle.clone()));
+
+    let reg = Registry::from_json(r#"{"times": [{"t": 0, "enabled_rules": ["Id"]}]}"#).unwrap();
+    assert_eq!(
+        transport(&cyclic, &reg, 0, 0),
+        Err(TransportError::InvalidInput(cycle))
+    );
+}
+
+#[test]
+fn unreachable_nodes_are_warnings() {
+    let p: Proof = serde_json::from_reader(
+        std::fs::File::open("examples/proof_with_unreachable_valid.json").unwrap(),
+    )
+    .unwrap();
+    assert!(validate_all(&p).is_empty());
+
+    let warnings = unreachable_warnings(&p);
+    assert!(!warnings.is_empty());
+    assert!(warnings
+        .iter()
+        .all(|d| d.severity == Severity::Warning && d.code == "unreachable"));
+}
+
+#[test]
+fn classical_proofs_use_multi_formula_succedents() {
+    let p: Proof = serde_json::from_reader(
+        std::fs::File::open("examples/classical_excluded_middle.json").unwrap(),
+    )
+    .unwrap();
+    assert_eq!(p.calculus, Calculus::Classical);
+    validate_local_wf(&p).expect("excluded middle holds classically");
+
+    // The same derivation is not intuitionistic.
+    let lj = Proof {
+        calculus: Calculus::Intuitionistic,
+        ..p.clone()
+    };
+    let err = validate_local_wf(&lj).unwrap_err().to_string();
+    assert!(err.contains("intuitionistic sequents have one"), "{err}");
+
+    // OrR needs both disjuncts on the right of its premise.
+    let mut bad = p.clone();
+    bad.nodes[3].premises = vec!["w".into()];
+    let err = validate_local_wf(&bad).unwrap_err().to_string();
+    assert!(err.contains("node lem fails rule OrR"), "{err}");
+
+    // Multi-formula succedents serialize as `succ` and come back unchanged.
+    let json = serde_json::to_value(&p).unwrap();
+    assert_eq!(json["calculus"], "classical");
+    assert_eq!(json["nodes"][1]["sequent"]["succ"][1], "⊥");
+    assert_eq!(serde_json::from_value::<Proof>(json).unwrap(), p);
+}
+
+#[test]
+fn intuitionistic_json_is_unchanged() {
+    let src = json!({
+        "nodes": [
+            { "id": "a", "rule": "Id", "premises": [], "sequent": { "ctx": [var("A")], "thm": var("A") } }
+        ],
+        "root": "a"
+    });
+    let p = proof(src.clone());
+    assert_eq!(p.calculus, Calculus::Intuitionistic);
+    assert_eq!(serde_json::to_value(&p).unwrap(), src);
+}
+
+#[test]
+fn validate_at_rejects_rules_not_enabled_at_t() {
+    let reg = Registry::from_path("examples/R.json").unwrap();
+    let p: Proof = serde_json::from_str(
+        &std::fs::read_to_string("examples/proof_with_cut_valid.json").unwrap(),
+    )
+    .unwrap();
+    validate_at(&p, &reg, 0).expect("Cut is enabled at t=0");
+    assert_eq!(
+        validate_at(&p, &reg, 1),
+        Err(ValidationError::RuleNotEnabled {
+            node: "n0".into(),
+            rule: "Cut".into(),
+            t: 1
+        })
+    );
+
+    // Before the first slice nothing is enabled.
+    let reg =
+        Registry::from_json(r#"{"times": [{"t": 3, "enabled_rules": ["Id", "Cut"]}]}"#).unwrap();
+    let diags = validate_all_at(&p, &reg, 2);
+    assert_eq!(diags.len(), 3);
+    assert!(diags.iter().all(|d| d.code == "rule-not-enabled"));
+}
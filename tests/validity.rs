@@ -0,0 +1,61 @@
+// tests/validity.rs
+use proof_transport::{
+    registry::Registry,
+    validity::{validity_intervals, Interval, Validity},
+};
+
+mod support;
+use support::load;
+
+#[test]
+fn intervals_cover_valid_transportable_and_impossible_times() {
+    let reg = Registry::from_json(
+        r#"{"times": [
+            {"t": 1, "enabled_rules": ["Id", "Cut"]},
+            {"t": 2, "enabled_rules": ["Id", "Cut", "AndR"]},
+            {"t": 4, "enabled_rules": ["Cut"]},
+            {"t": 7, "enabled_rules": ["Id"]}
+        ]}"#,
+    )
+    .unwrap();
+    let p = load("examples/proof_with_cut_valid.json");
+    let got = validity_intervals(&p, &reg).expect("well-formed");
+
+    let no_id = "no strategy removes the rules disabled at t={t}: n1 (Id), n2 (Id)";
+    let impossible = |t: u64| Validity::Impossible {
+        reason: no_id.replace("{t}", &t.to_string()),
+    };
+    let interval = |from, to, validity| Interval { from, to, validity };
+    assert_eq!(
+        got,
+        [
+            interval(0, Some(0), impossible(0)),
+            // Slices 1 and 2 differ, but not in a way the proof sees.
+            interval(1, Some(3), Validity::Valid),
+            interval(4, Some(6), impossible(4)),
+            interval(7, None, Validity::Transportable),
+        ]
+    );
+    assert_eq!(got[1].to_string(), "t=1..=3: valid");
+    assert_eq!(got[3].to_string(), "t>=7: valid after transport");
+}
+
+#[test]
+fn derivation_times_start_intervals() {
+    let reg = Registry::from_path("examples/kernel/R.json").unwrap();
+    let mut late = reg.clone();
+    for d in &mut late.derivations {
+        d.t = 3;
+    }
+    let p = load("examples/kernel/and_swap.json");
+
+    let got = validity_intervals(&p, &reg).unwrap();
+    assert_eq!(got.len(), 2);
+    assert_eq!(got[1].validity, Validity::Transportable);
+
+    // Between t=1 and the derivations at t=3 the schemas cannot be removed.
+    let got = validity_intervals(&p, &late).unwrap();
+    let spans: Vec<_> = got.iter().map(|i| (i.from, i.to)).collect();
+    assert_eq!(spans, [(0, Some(0)), (1, Some(2)), (3, None)]);
+    assert!(matches!(got[1].validity, Validity::Impossible { .. }));
+}